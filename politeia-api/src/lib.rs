@@ -1,3 +1,7 @@
+pub mod strict;
 pub mod v1;
 
+/// Mainnet politeiawww API host.
 pub const POLITEIA_HOST: &str = "https://proposals.decred.org/api";
+/// Testnet politeiawww API host.
+pub const TESTNET_POLITEIA_HOST: &str = "https://test-proposals.decred.org/api";
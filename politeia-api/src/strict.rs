@@ -0,0 +1,125 @@
+//! Strict deserialization, detecting drift between the API types and the
+//! JSON politeiawww sends.
+//!
+//! The API types are `#[serde(default)]`, a renamed or mistyped upstream
+//! field silently deserializes to its default. The helpers here compare the
+//! keys of the received JSON with the keys of the value re-serialized from
+//! the deserialized type.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Differences between received JSON and the type it was decoded into.
+///
+/// Fields are reported as paths such as `proposals[0].censorshiprecord.token`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// Fields present in the JSON but not known to the type, they are dropped
+    /// on deserialization.
+    pub unknown: Vec<String>,
+    /// Fields known to the type but absent from the JSON, they are defaulted
+    /// on deserialization.
+    pub missing: Vec<String>,
+}
+
+impl Drift {
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Errors decoding JSON in strict mode.
+#[derive(Debug)]
+pub enum StrictError {
+    /// JSON is malformed or does not match the type.
+    Json(serde_json::Error),
+    /// JSON contains fields unknown to the type.
+    UnknownFields(Vec<String>),
+}
+
+impl std::fmt::Display for StrictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrictError::Json(e) => write!(f, "{}", e),
+            StrictError::UnknownFields(fields) => {
+                write!(f, "unknown fields: {}", fields.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for StrictError {}
+
+impl From<serde_json::Error> for StrictError {
+    fn from(e: serde_json::Error) -> Self {
+        StrictError::Json(e)
+    }
+}
+
+/// Decodes `data` as `T` and reports the fields it does not round-trip.
+pub fn from_slice_with_drift<T>(data: &[u8]) -> Result<(T, Drift), serde_json::Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let received: Value = serde_json::from_slice(data)?;
+    let value: T = serde_json::from_value(received.clone())?;
+    let decoded = serde_json::to_value(&value)?;
+
+    let mut drift = Drift::default();
+    compare(&received, &decoded, String::new(), &mut drift);
+
+    Ok((value, drift))
+}
+
+/// Decodes `data` as `T`, denying fields unknown to `T`.
+///
+/// Missing fields are accepted as politeiawww omits empty optional fields,
+/// use [from_slice_with_drift] to report them.
+pub fn from_slice_strict<T>(data: &[u8]) -> Result<T, StrictError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let (value, drift) = from_slice_with_drift(data)?;
+
+    if !drift.unknown.is_empty() {
+        return Err(StrictError::UnknownFields(drift.unknown));
+    }
+
+    Ok(value)
+}
+
+fn compare(received: &Value, decoded: &Value, path: String, drift: &mut Drift) {
+    match (received, decoded) {
+        (Value::Object(received), Value::Object(decoded)) => {
+            for (key, value) in received {
+                let field = field_path(&path, key);
+                match decoded.get(key) {
+                    Some(e) => compare(value, e, field, drift),
+                    None => drift.unknown.push(field),
+                }
+            }
+
+            for key in decoded.keys() {
+                if !received.contains_key(key) {
+                    drift.missing.push(field_path(&path, key));
+                }
+            }
+        }
+
+        (Value::Array(received), Value::Array(decoded)) => {
+            for (i, (received, decoded)) in received.iter().zip(decoded).enumerate() {
+                compare(received, decoded, format!("{}[{}]", path, i), drift);
+            }
+        }
+
+        _ => {}
+    }
+}
+
+fn field_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
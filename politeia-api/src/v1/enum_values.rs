@@ -4,25 +4,30 @@
 ///   * PropStatusNotReviewed
 ///   * PropStatusUnreviewedChanges
 ///   * PropStatusCensored
+///
 /// PropStateVetted includes proposals with a status of:
 ///   * PropStatusPublic
 ///   * PropStatusAbandoned
 ///
 /// Proposal states correspond to the unvetted and vetted politeiad
 /// repositories.
-#[derive(serde::Deserialize, Debug)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProposalState {
+    #[default]
     Invalid,
     Unvetted,
     Vetted,
+    /// Code unknown to this crate.
+    Unknown(u8),
 }
 
-impl Into<ProposalState> for u8 {
-    fn into(self) -> ProposalState {
-        match self {
+impl From<u8> for ProposalState {
+    fn from(val: u8) -> ProposalState {
+        match val {
             1 => ProposalState::Unvetted,
             2 => ProposalState::Vetted,
-            _ => ProposalState::Invalid,
+            0 => ProposalState::Invalid,
+            _ => ProposalState::Unknown(val),
         }
     }
 }
@@ -32,19 +37,15 @@ impl From<ProposalState> for u8 {
             ProposalState::Unvetted => 1,
             ProposalState::Vetted => 2,
             ProposalState::Invalid => 0,
+            ProposalState::Unknown(code) => code,
         }
     }
 }
 
-impl Default for ProposalState {
-    fn default() -> Self {
-        ProposalState::Invalid
-    }
-}
-
 /// Proposal status codes.
-#[derive(serde::Deserialize, Debug)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProposalStatus {
+    #[default]
     Invalid,
     NotFound,
     NotReviewed,
@@ -52,24 +53,21 @@ pub enum ProposalStatus {
     Public,
     UnreviewedChanges,
     Abandoned,
+    /// Code unknown to this crate.
+    Unknown(u8),
 }
 
-impl Default for ProposalStatus {
-    fn default() -> Self {
-        ProposalStatus::Invalid
-    }
-}
-
-impl Into<ProposalStatus> for u8 {
-    fn into(self) -> ProposalStatus {
-        match self {
+impl From<u8> for ProposalStatus {
+    fn from(val: u8) -> ProposalStatus {
+        match val {
             1 => ProposalStatus::NotFound,
             2 => ProposalStatus::NotReviewed,
             3 => ProposalStatus::Censored,
             4 => ProposalStatus::Public,
             5 => ProposalStatus::UnreviewedChanges,
             6 => ProposalStatus::Abandoned,
-            _ => ProposalStatus::Invalid,
+            0 => ProposalStatus::Invalid,
+            _ => ProposalStatus::Unknown(val),
         }
     }
 }
@@ -84,27 +82,38 @@ impl From<ProposalStatus> for u8 {
             ProposalStatus::UnreviewedChanges => 5,
             ProposalStatus::Abandoned => 6,
             ProposalStatus::Invalid => 0,
+            ProposalStatus::Unknown(code) => code,
         }
     }
 }
 
-#[derive(serde::Deserialize, PartialEq, Debug)]
+/// Proposal vote status codes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProposalVoteStatus {
+    #[default]
     Invalid,
-    NotStarted,
+    /// Vote has not been authorized by the author.
+    NotAuthorized,
+    /// Vote has been authorized but not started by an admin.
+    Authorized,
     Started,
     Finished,
+    /// Proposal has no vote, e.g. it is not public.
     DoesntExist,
+    /// Code unknown to this crate.
+    Unknown(u8),
 }
 
-impl Into<ProposalVoteStatus> for u8 {
-    fn into(self) -> ProposalVoteStatus {
-        match self {
-            1 => ProposalVoteStatus::NotStarted,
-            2 => ProposalVoteStatus::Started,
-            3 => ProposalVoteStatus::Finished,
-            4 => ProposalVoteStatus::DoesntExist,
-            _ => ProposalVoteStatus::Invalid,
+impl From<u8> for ProposalVoteStatus {
+    fn from(val: u8) -> ProposalVoteStatus {
+        match val {
+            1 => ProposalVoteStatus::NotAuthorized,
+            2 => ProposalVoteStatus::Authorized,
+            3 => ProposalVoteStatus::Started,
+            4 => ProposalVoteStatus::Finished,
+            5 => ProposalVoteStatus::DoesntExist,
+            0 => ProposalVoteStatus::Invalid,
+            _ => ProposalVoteStatus::Unknown(val),
         }
     }
 }
@@ -112,17 +121,34 @@ impl Into<ProposalVoteStatus> for u8 {
 impl From<ProposalVoteStatus> for u8 {
     fn from(val: ProposalVoteStatus) -> u8 {
         match val {
-            ProposalVoteStatus::NotStarted => 1,
-            ProposalVoteStatus::Started => 2,
-            ProposalVoteStatus::Finished => 3,
-            ProposalVoteStatus::DoesntExist => 4,
+            ProposalVoteStatus::NotAuthorized => 1,
+            ProposalVoteStatus::Authorized => 2,
+            ProposalVoteStatus::Started => 3,
+            ProposalVoteStatus::Finished => 4,
+            ProposalVoteStatus::DoesntExist => 5,
             ProposalVoteStatus::Invalid => 0,
+            ProposalVoteStatus::Unknown(code) => code,
         }
     }
 }
 
-impl Default for ProposalVoteStatus {
-    fn default() -> Self {
-        ProposalVoteStatus::Invalid
-    }
+/// Implements serde for code enums as their numeric `u8` code.
+macro_rules! numeric_serde {
+    ($($name:ident),*) => {
+        $(
+            impl serde::Serialize for $name {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_u8((*self).into())
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    u8::deserialize(deserializer).map($name::from)
+                }
+            }
+        )*
+    };
 }
+
+numeric_serde!(ProposalState, ProposalStatus, ProposalVoteStatus);
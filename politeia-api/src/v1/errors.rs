@@ -1,57 +1,161 @@
-#[repr(u8)]
-pub enum ErrorCode {
-    Invalid = 0,
-    StatusProposalNotFound = 6,
-    StatusInvalidCensorshipToken = 58,
-    StatusMaxProposalsExceededPolicy = 61,
+/// Declares [ErrorCode] along with its numeric code and message.
+macro_rules! error_codes {
+    ($($variant:ident = $code:expr => $message:expr,)*) => {
+        /// Politeiawww v1 error status codes.
+        ///
+        /// Codes unknown to this crate are kept as [ErrorCode::Unknown] so
+        /// that converting to and from the `i64` politeiawww sends never
+        /// loses information.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum ErrorCode {
+            $($variant,)*
+            Unknown(i64),
+        }
+
+        impl ErrorCode {
+            /// Human readable description of the error code.
+            pub fn message(&self) -> &'static str {
+                match *self {
+                    $(ErrorCode::$variant => $message,)*
+                    ErrorCode::Unknown(_) => "Unknown error code.",
+                }
+            }
+        }
+
+        impl From<i64> for ErrorCode {
+            fn from(val: i64) -> Self {
+                match val {
+                    $($code => ErrorCode::$variant,)*
+                    _ => ErrorCode::Unknown(val),
+                }
+            }
+        }
+
+        impl From<ErrorCode> for i64 {
+            fn from(val: ErrorCode) -> Self {
+                match val {
+                    $(ErrorCode::$variant => $code,)*
+                    ErrorCode::Unknown(code) => code,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    Invalid = 0 => "Invalid error code.",
+    StatusInvalidPassword = 1 => "Invalid password.",
+    StatusMalformedEmail = 2 => "Malformed email.",
+    StatusVerificationTokenInvalid = 3 => "Invalid verification token.",
+    StatusVerificationTokenExpired = 4 => "Expired verification token.",
+    StatusProposalMissingFiles = 5 => "Missing proposal files.",
+    StatusProposalNotFound = 6 => "Proposal not found.",
+    StatusProposalDuplicateFilenames = 7 => "Duplicate proposal files.",
+    StatusProposalInvalidTitle = 8 => "Invalid proposal title.",
+    StatusMaxMdsExceededPolicy = 9 => "Maximum markdown files exceeded.",
+    StatusMaxImagesExceededPolicy = 10 => "Maximum image files exceeded.",
+    StatusMaxMdSizeExceededPolicy = 11 => "Maximum markdown file size exceeded.",
+    StatusMaxImageSizeExceededPolicy = 12 => "Maximum image file size exceeded.",
+    StatusMalformedPassword = 13 => "Malformed password.",
+    StatusCommentNotFound = 14 => "Comment not found.",
+    StatusInvalidFilename = 15 => "Invalid filename.",
+    StatusInvalidFileDigest = 16 => "Invalid file digest.",
+    StatusInvalidBase64 = 17 => "Invalid base64 file content.",
+    StatusInvalidMimeType = 18 => "Invalid MIME type detected for file.",
+    StatusUnsupportedMimeType = 19 => "Unsupported MIME type for file.",
+    StatusInvalidPropStatusTransition = 20 => "Invalid proposal status.",
+    StatusInvalidPublicKey = 21 => "Invalid public key.",
+    StatusNoPublicKey = 22 => "No active public key.",
+    StatusInvalidSignature = 23 => "Invalid signature.",
+    StatusInvalidInput = 24 => "Invalid input.",
+    StatusInvalidSigningKey = 25 => "Invalid signing key.",
+    StatusCommentLengthExceededPolicy = 26 => "Maximum comment length exceeded.",
+    StatusUserNotFound = 27 => "User not found.",
+    StatusWrongStatus = 28 => "Wrong proposal status.",
+    StatusNotLoggedIn = 29 => "User not logged in.",
+    StatusUserNotPaid = 30 => "User hasn't paid paywall.",
+    StatusReviewerAdminEqualsAuthor = 31 => "User cannot change the status of their own proposal.",
+    StatusMalformedUsername = 32 => "Malformed username.",
+    StatusDuplicateUsername = 33 => "Duplicate username.",
+    StatusVerificationTokenUnexpired = 34 => "Verification token not yet expired.",
+    StatusCannotVerifyPayment = 35 => "Cannot verify payment at this time.",
+    StatusDuplicatePublicKey = 36 => "Public key already taken by another user.",
+    StatusInvalidPropVoteStatus = 37 => "Invalid proposal vote status.",
+    StatusUserLocked = 38 => "User locked due to too many login attempts.",
+    StatusNoProposalCredits = 39 => "No proposal credits.",
+    StatusInvalidUserManageAction = 40 => "Invalid user edit action.",
+    StatusUserActionNotAllowed = 41 => "User action is not allowed.",
+    StatusWrongVoteStatus = 42 => "Wrong proposal vote status.",
+    StatusUnused1 = 43 => "Unused status.",
+    StatusCannotVoteOnPropComment = 44 => "Cannot vote on proposal comment.",
+    StatusChangeMessageCannotBeBlank = 45 => "Status change message cannot be blank.",
+    StatusCensorReasonCannotBeBlank = 46 => "Censor comment reason cannot be blank.",
+    StatusCannotCensorComment = 47 => "Cannot censor comment.",
+    StatusUserNotAuthor = 48 => "User is not the proposal author.",
+    StatusVoteNotAuthorized = 49 => "Vote has not been authorized.",
+    StatusVoteAlreadyAuthorized = 50 => "Vote has already been authorized.",
+    StatusInvalidAuthVoteAction = 51 => "Invalid authorize vote action.",
+    StatusUserDeactivated = 52 => "User account is deactivated.",
+    StatusInvalidPropVoteBits = 53 => "Invalid proposal vote option bits.",
+    StatusInvalidPropVoteParams = 54 => "Invalid proposal vote parameters.",
+    StatusEmailNotVerified = 55 => "Email address is not verified.",
+    StatusInvalidUuid = 56 => "Invalid user UUID.",
+    StatusInvalidLikeCommentAction = 57 => "Invalid like comment action.",
+    StatusInvalidCensorshipToken = 58 => "Invalid censorship token.",
+    StatusEmailAlreadyVerified = 59 => "Email address is already verified.",
+    StatusNoProposalChanges = 60 => "No changes found in proposal.",
+    StatusMaxProposalsExceededPolicy = 61 => "Max proposal per request exceed.",
+    StatusDuplicateComment = 62 => "Duplicate comment.",
+    StatusInvalidLogin = 63 => "Invalid login credentials.",
+    StatusCommentIsCensored = 64 => "Comment is censored.",
+    StatusInvalidProposalVersion = 65 => "Invalid proposal version.",
+    StatusMetadataInvalid = 66 => "Invalid metadata.",
+    StatusMetadataMissing = 67 => "Missing metadata.",
+    StatusMetadataDigestInvalid = 68 => "Metadata digest invalid.",
+    StatusInvalidVoteType = 69 => "Invalid vote type.",
+    StatusInvalidVoteOptions = 70 => "Invalid vote options.",
+    StatusLinkByDeadlineNotMet = 71 => "Linkby deadline not met yet.",
+    StatusNoLinkedProposals = 72 => "No linked proposals.",
+    StatusInvalidLinkTo = 73 => "Invalid proposal linkto.",
+    StatusInvalidLinkBy = 74 => "Invalid proposal linkby.",
+    StatusInvalidRunoffVote = 75 => "Invalid runoff vote.",
+    StatusWrongProposalType = 76 => "Wrong proposal type.",
 }
 
 impl std::fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
-            ErrorCode::Invalid => write!(f, "Invalid error code."),
-            ErrorCode::StatusProposalNotFound => write!(f, "Proposal not found."),
-            ErrorCode::StatusInvalidCensorshipToken => write!(f, "Invalid censorship token."),
-            ErrorCode::StatusMaxProposalsExceededPolicy => {
-                write!(f, "Max proposal per request exceed.")
-            }
+            ErrorCode::Unknown(code) => write!(f, "Unknown error code {}.", code),
+            _ => write!(f, "{}", self.message()),
         }
     }
 }
 
-impl std::fmt::Debug for ErrorCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            ErrorCode::Invalid => write!(f, "ErrorCode(Invalid error code)"),
-            ErrorCode::StatusProposalNotFound => write!(f, "ErrorCode(Proposal not found)"),
-            ErrorCode::StatusInvalidCensorshipToken => {
-                write!(f, "ErrorCode(Invalid censorship token)")
-            }
-            ErrorCode::StatusMaxProposalsExceededPolicy => {
-                write!(f, "ErrorCode(Max proposal per request exceed)")
-            }
-        }
+impl From<u8> for ErrorCode {
+    fn from(val: u8) -> Self {
+        ErrorCode::from(i64::from(val))
     }
 }
 
-impl Into<ErrorCode> for u8 {
-    fn into(self) -> ErrorCode {
-        match self {
-            6 => ErrorCode::StatusProposalNotFound,
-            58 => ErrorCode::StatusInvalidCensorshipToken,
-            61 => ErrorCode::StatusMaxProposalsExceededPolicy,
-            _ => ErrorCode::Invalid,
-        }
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64((*self).into())
     }
 }
 
-impl From<ErrorCode> for u8 {
-    fn from(val: ErrorCode) -> Self {
-        match val {
-            ErrorCode::Invalid => 0,
-            ErrorCode::StatusProposalNotFound => 6,
-            ErrorCode::StatusInvalidCensorshipToken => 58,
-            ErrorCode::StatusMaxProposalsExceededPolicy => 61,
-        }
+impl<'de> serde::Deserialize<'de> for ErrorCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(ErrorCode::from)
     }
 }
+
+/// Error body returned by politeiawww when a request fails on user input.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct UserError {
+    /// Politeia error code.
+    #[serde(rename = "errorcode")]
+    pub error_code: i64,
+    /// Additional information about the error.
+    #[serde(rename = "errorcontext", default)]
+    pub error_context: Vec<String>,
+}
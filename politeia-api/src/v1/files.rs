@@ -0,0 +1,113 @@
+use super::types::{File, Proposal};
+use sha2::{Digest, Sha256};
+
+/// Name of the proposal index file when the policy does not provide one.
+pub const DEFAULT_INDEX_FILENAME: &str = "index.md";
+
+/// Errors decoding a proposal [File].
+#[derive(Debug)]
+pub enum FileError {
+    /// Payload is not valid base64.
+    Base64(base64::DecodeError),
+    /// SHA256 digest of the decoded payload does not match the file digest.
+    DigestMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    /// Payload is not valid UTF-8 text.
+    Utf8(std::string::FromUtf8Error),
+    /// Proposal has no file with the given name.
+    NotFound(String),
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Base64(e) => write!(f, "invalid base64 payload: {}", e),
+            FileError::DigestMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "digest mismatch for {}, expected {} got {}",
+                name, expected, actual
+            ),
+            FileError::Utf8(e) => write!(f, "invalid utf-8 payload: {}", e),
+            FileError::NotFound(name) => write!(f, "file {} not found", name),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+impl From<base64::DecodeError> for FileError {
+    fn from(e: base64::DecodeError) -> Self {
+        FileError::Base64(e)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for FileError {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        FileError::Utf8(e)
+    }
+}
+
+/// Hex encoded SHA256 digest of data.
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+impl File {
+    /// Decodes the base64 payload without checking its digest.
+    pub fn decode_payload(&self) -> Result<Vec<u8>, FileError> {
+        Ok(base64::decode(&self.payload)?)
+    }
+
+    /// Decodes the payload and ensures it matches the file digest.
+    pub fn verified_payload(&self) -> Result<Vec<u8>, FileError> {
+        let payload = self.decode_payload()?;
+
+        let actual = sha256_hex(&payload);
+        if !actual.eq_ignore_ascii_case(&self.digest) {
+            return Err(FileError::DigestMismatch {
+                name: self.name.clone(),
+                expected: self.digest.clone(),
+                actual,
+            });
+        }
+
+        Ok(payload)
+    }
+}
+
+impl Proposal {
+    /// Returns the proposal file with the given name.
+    pub fn file(&self, name: &str) -> Option<&File> {
+        self.files.iter().find(|file| file.name == name)
+    }
+
+    /// Returns the proposal index file, `index_filename` is usually
+    /// [Policy::index_filename](super::types::Policy::index_filename).
+    pub fn index_file(&self, index_filename: &str) -> Option<&File> {
+        self.file(index_name(index_filename))
+    }
+
+    /// Returns the verified markdown body of the proposal index file.
+    pub fn index_markdown(&self, index_filename: &str) -> Result<String, FileError> {
+        let file = self
+            .index_file(index_filename)
+            .ok_or_else(|| FileError::NotFound(index_name(index_filename).to_string()))?;
+
+        Ok(String::from_utf8(file.verified_payload()?)?)
+    }
+}
+
+fn index_name(index_filename: &str) -> &str {
+    if index_filename.is_empty() {
+        DEFAULT_INDEX_FILENAME
+    } else {
+        index_filename
+    }
+}
@@ -0,0 +1,171 @@
+use super::{
+    enum_values::ProposalStatus,
+    files::sha256_hex,
+    types::{Metadata, Proposal},
+};
+
+/// Hint of the user submitted proposal metadata.
+pub const HINT_PROPOSAL_METADATA: &str = "proposalmetadata";
+/// Hint of a proposal status change record.
+pub const HINT_STATUS_CHANGE: &str = "statuschange";
+
+/// Proposal metadata submitted along with the proposal files.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct ProposalMetadata {
+    /// Proposal name.
+    pub name: String,
+    /// Token of the RFP the proposal is submitted to.
+    #[serde(rename = "linkto")]
+    pub link_to: String,
+    /// UNIX timestamp of the RFP deadline.
+    #[serde(rename = "linkby")]
+    pub link_by: i64,
+}
+
+/// Record of a proposal status change.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct StatusChange {
+    /// Proposal censorship token.
+    pub token: String,
+    /// Proposal version the status change applies to.
+    pub version: String,
+    /// New proposal status.
+    pub status: ProposalStatus,
+    /// Reason of the status change.
+    pub message: String,
+    /// Signature of the admin that changed the status.
+    pub signature: String,
+    /// Public key of the admin that changed the status.
+    #[serde(rename = "publickey")]
+    pub public_key: String,
+    /// UNIX timestamp of the status change.
+    pub timestamp: i64,
+}
+
+/// Decoded [Metadata] payload.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MetadataPayload {
+    ProposalMetadata(ProposalMetadata),
+    StatusChange(StatusChange),
+    /// Payload of a hint unknown to this crate.
+    Raw {
+        hint: String,
+        payload: Vec<u8>,
+    },
+}
+
+/// Errors decoding a proposal [Metadata].
+#[derive(Debug)]
+pub enum MetadataError {
+    /// Payload is not valid base64.
+    Base64(base64::DecodeError),
+    /// SHA256 digest of the decoded payload does not match the metadata digest.
+    DigestMismatch {
+        hint: String,
+        expected: String,
+        actual: String,
+    },
+    /// Payload of a known hint is not valid JSON for its type.
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::Base64(e) => write!(f, "invalid base64 payload: {}", e),
+            MetadataError::DigestMismatch {
+                hint,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "digest mismatch for {} metadata, expected {} got {}",
+                hint, expected, actual
+            ),
+            MetadataError::Json(e) => write!(f, "invalid metadata payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {}
+
+impl From<base64::DecodeError> for MetadataError {
+    fn from(e: base64::DecodeError) -> Self {
+        MetadataError::Base64(e)
+    }
+}
+
+impl From<serde_json::Error> for MetadataError {
+    fn from(e: serde_json::Error) -> Self {
+        MetadataError::Json(e)
+    }
+}
+
+impl Metadata {
+    /// Decodes the payload after checking it matches the metadata digest.
+    ///
+    /// Known hints are decoded into their typed structure, any other hint is
+    /// returned as [MetadataPayload::Raw].
+    pub fn decode(&self) -> Result<MetadataPayload, MetadataError> {
+        let payload = base64::decode(&self.payload)?;
+
+        let actual = sha256_hex(&payload);
+        if !actual.eq_ignore_ascii_case(&self.digest) {
+            return Err(MetadataError::DigestMismatch {
+                hint: self.hint.clone(),
+                expected: self.digest.clone(),
+                actual,
+            });
+        }
+
+        match self.hint.as_str() {
+            HINT_PROPOSAL_METADATA => Ok(MetadataPayload::ProposalMetadata(
+                serde_json::from_slice(&payload)?,
+            )),
+            HINT_STATUS_CHANGE => Ok(MetadataPayload::StatusChange(serde_json::from_slice(
+                &payload,
+            )?)),
+            _ => Ok(MetadataPayload::Raw {
+                hint: self.hint.clone(),
+                payload,
+            }),
+        }
+    }
+}
+
+impl Proposal {
+    /// Decodes every metadata of the proposal.
+    pub fn decoded_metadata(&self) -> Result<Vec<MetadataPayload>, MetadataError> {
+        self.metadata.iter().map(Metadata::decode).collect()
+    }
+
+    /// Returns the decoded proposal metadata, if the proposal carries one.
+    pub fn proposal_metadata(&self) -> Result<Option<ProposalMetadata>, MetadataError> {
+        for metadata in &self.metadata {
+            if metadata.hint == HINT_PROPOSAL_METADATA {
+                if let MetadataPayload::ProposalMetadata(e) = metadata.decode()? {
+                    return Ok(Some(e));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the decoded status changes of the proposal, oldest first.
+    pub fn status_changes(&self) -> Result<Vec<StatusChange>, MetadataError> {
+        let mut changes = Vec::new();
+        for metadata in &self.metadata {
+            if metadata.hint == HINT_STATUS_CHANGE {
+                if let MetadataPayload::StatusChange(e) = metadata.decode()? {
+                    changes.push(e);
+                }
+            }
+        }
+
+        changes.sort_by_key(|change| change.timestamp);
+        Ok(changes)
+    }
+}
@@ -1,4 +1,7 @@
 pub mod enum_values;
 pub mod errors;
+pub mod files;
+pub mod metadata;
 pub mod routes;
 pub mod types;
+pub mod verify;
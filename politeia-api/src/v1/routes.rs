@@ -8,3 +8,10 @@ pub const REQUEST_GET_POLICY: &str = "/v1/policy";
 pub const REQUEST_GET_VERSION: &str = "/v1/version";
 /// Retrieve politeia proposals provided tokens.
 pub const REQUEST_POST_BATCH_PROPOSALS: &str = "/v1/proposals/batch";
+/// Retrieve the vote summaries of politeia proposals provided tokens.
+pub const REQUEST_POST_BATCH_VOTE_SUMMARY: &str = "/v1/proposals/batchvotesummary";
+/// Retrieve a single proposal, `{token}` is replaced by the censorship token.
+/// An optional `version` query parameter selects a prior proposal version.
+pub const REQUEST_GET_PROPOSAL_DETAILS: &str = "/v1/proposals/{token}";
+/// Retrieve the comments of a proposal, `{token}` is replaced by the censorship token.
+pub const REQUEST_GET_COMMENTS: &str = "/v1/proposals/{token}/comments";
@@ -1,4 +1,6 @@
 #![allow(deprecated)]
+use super::enum_values::{ProposalState, ProposalStatus, ProposalVoteStatus};
+
 /// Obtain version, route information and signing identity from server.
 ///
 /// This call shall ALWAYS be the first contact with the server.
@@ -7,13 +9,21 @@
 #[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(default)]
 pub struct Version {
-    pub version: String,
+    /// Highest API version supported by the server.
+    pub version: u32,
+    /// Route prefix of the API version, e.g `/v1`.
     pub route: String,
+    /// Server build version.
+    #[serde(rename = "buildversion")]
+    pub build_version: String,
+    /// Server public signing key.
     pub pubkey: String,
     pub testnet: bool,
+    /// Server mode, `piwww` or `cmswww`.
     pub mode: String,
-    #[serde(rename = "activeusersesstion")]
-    pub active_user_sesstion: String,
+    /// Whether the request carried a valid user session cookie.
+    #[serde(rename = "activeusersession")]
+    pub active_user_session: bool,
 }
 
 /// Describes a single vote option.
@@ -45,8 +55,8 @@ pub struct VoteOptionResult {
 pub struct VoteStatus {
     /// Token that identifies vote.
     pub token: String,
-    /// Proposal status.
-    pub status: u8,
+    /// Proposal vote status.
+    pub status: ProposalVoteStatus,
     /// Proposal's total number of votes.
     #[serde(rename = "totalvotes")]
     pub total_votes: u64,
@@ -79,6 +89,46 @@ pub struct VoteStatusResult {
     pub vote_status: Vec<VoteStatus>,
 }
 
+/// Summary of the vote of a single proposal.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct VoteSummary {
+    /// Vote status.
+    pub status: ProposalVoteStatus,
+    /// Vote type.
+    #[serde(rename = "type")]
+    pub vote_type: u8,
+    /// Whether the proposal was approved.
+    pub approved: bool,
+    /// Number of tickets eligible to vote.
+    #[serde(rename = "eligibletickets")]
+    pub eligible_tickets: u32,
+    /// Vote duration in blocks.
+    pub duration: u32,
+    /// Vote end height.
+    #[serde(rename = "endheight")]
+    pub end_height: u64,
+    /// Percent of eligible votes required for quorum.
+    #[serde(rename = "quorumpercentage")]
+    pub quorum_percentage: u32,
+    /// Percent of total votes required to pass.
+    #[serde(rename = "passpercentage")]
+    pub pass_percentage: u32,
+    /// Vote option results.
+    pub results: Vec<VoteOptionResult>,
+}
+
+/// Returns the [VoteSummary] of the requested proposals.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct BatchVoteSummaryResult {
+    /// Current best block height.
+    #[serde(rename = "bestblock")]
+    pub best_block: u64,
+    /// Vote summaries keyed by proposal token.
+    pub summaries: std::collections::HashMap<String, VoteSummary>,
+}
+
 /// Retrieve the censorship record tokens of all proposals in the inventory.
 #[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(default)]
@@ -99,6 +149,66 @@ pub struct TokenInventory {
     pub censored: Vec<String>,
 }
 
+impl TokenInventory {
+    /// Names of the inventory buckets, matching their JSON keys.
+    pub const BUCKETS: [&'static str; 7] = [
+        "pre",
+        "active",
+        "approved",
+        "rejected",
+        "abandoned",
+        "unreviewed",
+        "censored",
+    ];
+
+    /// Returns the tokens of the named bucket.
+    pub fn bucket(&self, name: &str) -> Option<&Vec<String>> {
+        match name {
+            "pre" => Some(&self.pre),
+            "active" => Some(&self.active),
+            "approved" => Some(&self.approved),
+            "rejected" => Some(&self.rejected),
+            "abandoned" => Some(&self.abandoned),
+            "unreviewed" => Some(&self.unreviewed),
+            "censored" => Some(&self.censored),
+            _ => None,
+        }
+    }
+
+    /// Returns the tokens of the named bucket for modification.
+    pub fn bucket_mut(&mut self, name: &str) -> Option<&mut Vec<String>> {
+        match name {
+            "pre" => Some(&mut self.pre),
+            "active" => Some(&mut self.active),
+            "approved" => Some(&mut self.approved),
+            "rejected" => Some(&mut self.rejected),
+            "abandoned" => Some(&mut self.abandoned),
+            "unreviewed" => Some(&mut self.unreviewed),
+            "censored" => Some(&mut self.censored),
+            _ => None,
+        }
+    }
+
+    /// Iterates over every bucket name along with its tokens.
+    pub fn buckets(&self) -> impl Iterator<Item = (&'static str, &Vec<String>)> {
+        Self::BUCKETS
+            .iter()
+            .filter_map(move |name| self.bucket(name).map(|tokens| (*name, tokens)))
+    }
+
+    /// Returns the name of the bucket holding token.
+    pub fn bucket_of(&self, token: &str) -> Option<&'static str> {
+        self.buckets()
+            .find(|(_, tokens)| tokens.iter().any(|t| t == token))
+            .map(|(name, _)| name)
+    }
+
+    /// Iterates over every token in the inventory.
+    pub fn all_tokens(&self) -> impl Iterator<Item = &String> {
+        self.buckets().flat_map(|(_, tokens)| tokens.iter())
+    }
+}
+
 /// Describes an individual file that is part of the proposal.
 #[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(default)]
@@ -146,10 +256,10 @@ pub struct Proposal {
     pub name: String,
     /// Current state of proposal.
     #[serde(rename = "state")]
-    pub proposal_state: i64,
+    pub proposal_state: ProposalState,
     /// Current status of proposal.
     #[serde(rename = "status")]
-    pub proposal_status: i64,
+    pub proposal_status: ProposalStatus,
     /// Last update of proposal.
     pub timestamp: i64,
     /// ID of user who submitted proposal.
@@ -185,6 +295,9 @@ pub struct Proposal {
     /// UNIX timestamp of RFP deadline.
     #[serde(rename = "linkby")]
     pub link_by: i64,
+    /// Tokens of the submissions linking to this RFP.
+    #[serde(rename = "linkedfrom")]
+    pub linked_from: Vec<String>,
     /// Proposal files.
     pub files: Vec<File>,
     /// Proposal metadata.
@@ -201,6 +314,62 @@ pub struct ProposalsResult {
     pub proposals: Vec<Proposal>,
 }
 
+/// Returns a single Politeia [Proposal].
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct ProposalDetailsResult {
+    pub proposal: Proposal,
+}
+
+/// Describes a comment on a proposal.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct Comment {
+    /// Censorship token of the commented proposal.
+    pub token: String,
+    /// Comment ID of the parent comment, "0" for top level comments.
+    #[serde(rename = "parentid")]
+    pub parent_id: String,
+    /// Comment text.
+    pub comment: String,
+    /// Client signature of Token+ParentID+Comment.
+    pub signature: String,
+    /// Public key used for signature.
+    #[serde(rename = "publickey")]
+    pub public_key: String,
+    /// Comment ID unique to the proposal.
+    #[serde(rename = "commentid")]
+    pub comment_id: String,
+    /// Server signature of the client signature.
+    pub receipt: String,
+    /// UNIX timestamp of the last update.
+    pub timestamp: i64,
+    /// Vote score.
+    #[serde(rename = "resultvotes")]
+    pub result_votes: i64,
+    /// Number of upvotes.
+    pub upvotes: u64,
+    /// Number of downvotes.
+    pub downvotes: u64,
+    /// Has the comment been censored.
+    pub censored: bool,
+    /// ID of the comment author.
+    #[serde(rename = "userid")]
+    pub user_id: String,
+    /// Username of the comment author.
+    pub username: String,
+}
+
+/// Returns the [Comment]s of a proposal.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(default)]
+pub struct GetCommentsResult {
+    pub comments: Vec<Comment>,
+    /// UNIX timestamp of the last access time, only set for logged in users.
+    #[serde(rename = "accesstime")]
+    pub access_time: i64,
+}
+
 /// Retrieve server policy.
 ///
 /// The returned values contain various maxima that the client SHALL observe.
@@ -255,7 +424,7 @@ pub struct Policy {
     /// Maximum number of characters accepted for comments.
     #[serde(rename = "maxcommentlength")]
     pub max_comment_length: usize,
-    /// The length of token prefix needed.
+    /// Politeiad public key censorship records are signed with.
     #[serde(rename = "backendpublickey")]
     pub backend_public_key: String,
     /// The length of token prefix needed.
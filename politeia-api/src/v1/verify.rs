@@ -0,0 +1,131 @@
+use super::{files::FileError, types::Proposal};
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+
+/// Errors verifying a proposal [CensorshipRecord](super::types::CensorshipRecord).
+#[derive(Debug)]
+pub enum VerifyError {
+    /// A proposal file does not match its digest.
+    File(FileError),
+    /// A digest, key or signature is not valid hex.
+    Hex(hex::FromHexError),
+    /// Merkle root of the proposal digests does not match the censorship record.
+    MerkleMismatch { expected: String, actual: String },
+    /// Backend public key is malformed.
+    PublicKey(ed25519_dalek::SignatureError),
+    /// Censorship record signature is malformed or does not verify.
+    Signature(ed25519_dalek::SignatureError),
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::File(e) => write!(f, "{}", e),
+            VerifyError::Hex(e) => write!(f, "invalid hex: {}", e),
+            VerifyError::MerkleMismatch { expected, actual } => write!(
+                f,
+                "merkle root mismatch, expected {} got {}",
+                expected, actual
+            ),
+            VerifyError::PublicKey(e) => write!(f, "invalid backend public key: {}", e),
+            VerifyError::Signature(e) => write!(f, "invalid censorship signature: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<FileError> for VerifyError {
+    fn from(e: FileError) -> Self {
+        VerifyError::File(e)
+    }
+}
+
+impl From<hex::FromHexError> for VerifyError {
+    fn from(e: hex::FromHexError) -> Self {
+        VerifyError::Hex(e)
+    }
+}
+
+/// Computes the merkle root of SHA256 digests.
+///
+/// Pairs are hashed as `SHA256(left || right)`, an odd node at any level is
+/// paired with itself. The root of a single digest is the digest itself.
+pub fn merkle_root(digests: &[[u8; 32]]) -> [u8; 32] {
+    if digests.is_empty() {
+        return [0; 32];
+    }
+
+    let mut level = digests.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).unwrap_or(&pair[0]);
+
+                let mut hasher = Sha256::new();
+                hasher.update(pair[0]);
+                hasher.update(right);
+
+                let mut node = [0; 32];
+                node.copy_from_slice(&hasher.finalize());
+                node
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+fn decode_digest(digest: &str) -> Result<[u8; 32], VerifyError> {
+    let mut out = [0; 32];
+    hex::decode_to_slice(digest, &mut out)?;
+    Ok(out)
+}
+
+impl Proposal {
+    /// Recomputes the merkle root of the proposal files and metadata.
+    pub fn merkle_root(&self) -> Result<String, VerifyError> {
+        let digests = self
+            .files
+            .iter()
+            .map(|file| file.digest.as_str())
+            .chain(self.metadata.iter().map(|md| md.digest.as_str()))
+            .map(decode_digest)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(hex::encode(merkle_root(&digests)))
+    }
+
+    /// Verifies the proposal censorship record.
+    ///
+    /// Checks every file payload matches its digest, the merkle root of the
+    /// digests matches the censorship record and the record signature over
+    /// `merkle+token` verifies against `backend_public_key`, usually
+    /// [Policy::backend_public_key](super::types::Policy::backend_public_key).
+    pub fn verify(&self, backend_public_key: &str) -> Result<(), VerifyError> {
+        for file in &self.files {
+            file.verified_payload()?;
+        }
+
+        let record = &self.censorship_record;
+
+        let merkle = self.merkle_root()?;
+        if !merkle.eq_ignore_ascii_case(&record.merkle) {
+            return Err(VerifyError::MerkleMismatch {
+                expected: record.merkle.clone(),
+                actual: merkle,
+            });
+        }
+
+        let public_key = ed25519_dalek::PublicKey::from_bytes(&hex::decode(backend_public_key)?)
+            .map_err(VerifyError::PublicKey)?;
+        let signature = ed25519_dalek::Signature::try_from(&hex::decode(&record.signature)?[..])
+            .map_err(VerifyError::Signature)?;
+
+        let message = format!("{}{}", record.merkle, record.token);
+        public_key
+            .verify_strict(message.as_bytes(), &signature)
+            .map_err(VerifyError::Signature)
+    }
+}
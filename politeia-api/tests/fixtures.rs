@@ -0,0 +1,262 @@
+#![allow(deprecated)]
+
+use politeia_api::{
+    strict::{self, Drift, StrictError},
+    v1::{
+        enum_values::{ProposalStatus, ProposalVoteStatus},
+        errors, types,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Fields politeiawww tags `omitempty`, they may be absent from responses.
+const OMITTED_WHEN_EMPTY: [&str; 11] = [
+    "statuschangemessage",
+    "publishedat",
+    "censoredat",
+    "abandonedat",
+    "linkto",
+    "linkby",
+    "linkedfrom",
+    "upvotes",
+    "downvotes",
+    "accesstime",
+    "errorcontext",
+];
+
+fn read(name: &str) -> Vec<u8> {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path, e))
+}
+
+/// Decodes a fixture, failing on unknown fields and on missing fields
+/// politeiawww always sends.
+fn decode<T: DeserializeOwned + Serialize>(name: &str) -> T {
+    let (value, drift) = strict::from_slice_with_drift::<T>(&read(name))
+        .unwrap_or_else(|e| panic!("decoding {}: {}", name, e));
+
+    assert!(
+        drift.unknown.is_empty(),
+        "{} has unknown fields {:?}",
+        name,
+        drift.unknown
+    );
+
+    let missing: Vec<&String> = drift
+        .missing
+        .iter()
+        .filter(|path| {
+            let field = path.rsplit('.').next().unwrap();
+            !OMITTED_WHEN_EMPTY.contains(&field)
+        })
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "{} is missing fields {:?}",
+        name,
+        missing
+    );
+
+    value
+}
+
+#[test]
+fn version() {
+    let version: types::Version = decode("version.json");
+
+    assert_eq!(version.version, 1);
+    assert_eq!(version.route, "/v1");
+    assert_eq!(version.mode, "piwww");
+}
+
+#[test]
+fn policy() {
+    let policy: types::Policy = decode("policy.json");
+
+    assert_eq!(policy.proposal_list_page_size, 20);
+    assert_eq!(policy.token_prefix_length, 7);
+    assert_eq!(policy.index_filename, "index.md");
+    assert_eq!(policy.backend_public_key.len(), 64);
+    assert_eq!(policy.max_vote_duration, 4032);
+}
+
+#[test]
+fn token_inventory() {
+    let inventory: types::TokenInventory = decode("tokeninventory.json");
+
+    assert!(inventory.active.is_empty());
+    assert_eq!(inventory.all_tokens().count(), 6);
+    assert_eq!(
+        inventory.bucket_of(&inventory.censored[0]),
+        Some("censored")
+    );
+}
+
+#[test]
+fn batch_proposals() {
+    let result: types::ProposalsResult = decode("batchproposals.json");
+    assert_eq!(result.proposals.len(), 3);
+
+    let rfp = &result.proposals[0];
+    assert_eq!(rfp.number_of_comments, 12);
+    assert_eq!(rfp.version, "2");
+    assert_eq!(rfp.link_by, 1604500000);
+    assert_eq!(
+        rfp.linked_from,
+        vec![result.proposals[1].censorship_record.token.clone()]
+    );
+    assert_eq!(rfp.censorship_record.token.len(), 64);
+    assert_eq!(
+        rfp.index_markdown("index.md").unwrap().lines().next(),
+        Some("# RFP")
+    );
+    assert_eq!(rfp.proposal_metadata().unwrap().unwrap().name, rfp.name);
+
+    assert_eq!(result.proposals[1].link_to, rfp.censorship_record.token);
+
+    let abandoned = &result.proposals[2];
+    assert_eq!(abandoned.proposal_status, ProposalStatus::Abandoned);
+    assert_eq!(abandoned.status_change_message, "Author stopped responding");
+}
+
+#[test]
+fn proposal_details() {
+    let result: types::ProposalDetailsResult = decode("proposaldetails.json");
+
+    assert_eq!(result.proposal.proposal_status, ProposalStatus::Censored);
+    assert_eq!(result.proposal.censored_at, 1591000000);
+    assert_eq!(result.proposal.pubished_at, 0);
+}
+
+#[test]
+fn fixture_proposals_verify() {
+    let policy: types::Policy = decode("policy.json");
+    let batch: types::ProposalsResult = decode("batchproposals.json");
+    let details: types::ProposalDetailsResult = decode("proposaldetails.json");
+
+    for proposal in batch.proposals.iter().chain(Some(&details.proposal)) {
+        proposal
+            .verify(&policy.backend_public_key)
+            .unwrap_or_else(|e| panic!("{}: {}", proposal.censorship_record.token, e));
+    }
+}
+
+#[test]
+fn batch_vote_summary() {
+    let result: types::BatchVoteSummaryResult = decode("batchvotesummary.json");
+
+    assert_eq!(result.best_block, 492118);
+    assert_eq!(result.summaries.len(), 2);
+
+    let finished =
+        &result.summaries["42b4be24237352db81085e5aefb803ebaafa6c63a876c41cdac44cee11e8c82e"];
+    assert_eq!(finished.status, ProposalVoteStatus::Finished);
+    assert!(finished.approved);
+    assert_eq!(finished.results.len(), 2);
+
+    let unauthorized =
+        &result.summaries["8ada057c61f1b3e6526089b26099767740a15c51e587066adcb28ef6f805d9dd"];
+    assert_eq!(unauthorized.status, ProposalVoteStatus::NotAuthorized);
+}
+
+#[test]
+fn vote_status() {
+    let result: types::VoteStatusResult = decode("votestatus.json");
+
+    assert_eq!(result.vote_status.len(), 1);
+    assert_eq!(result.vote_status[0].status, ProposalVoteStatus::Finished);
+    assert_eq!(result.vote_status[0].total_votes, 11435);
+    assert_eq!(result.vote_status[0].end_height, "489732");
+}
+
+#[test]
+fn comments() {
+    let result: types::GetCommentsResult = decode("comments.json");
+
+    assert_eq!(result.comments.len(), 3);
+    assert_eq!(result.comments[1].parent_id, "1");
+    assert_eq!(result.comments[2].result_votes, -2);
+    assert!(result.comments[2].censored);
+}
+
+#[test]
+fn user_error() {
+    let error: errors::UserError = decode("usererror.json");
+
+    assert_eq!(
+        errors::ErrorCode::from(error.error_code),
+        errors::ErrorCode::StatusMaxProposalsExceededPolicy
+    );
+    assert_eq!(error.error_context.len(), 1);
+}
+
+#[test]
+fn error_codes_round_trip() {
+    for code in 0..=u8::MAX {
+        let error_code = errors::ErrorCode::from(code);
+        assert_eq!(i64::from(error_code), i64::from(code));
+
+        let json = serde_json::to_string(&error_code).unwrap();
+        assert_eq!(json, code.to_string());
+        assert_eq!(
+            serde_json::from_str::<errors::ErrorCode>(&json).unwrap(),
+            error_code
+        );
+    }
+
+    assert_eq!(
+        errors::ErrorCode::from(61u8),
+        errors::ErrorCode::StatusMaxProposalsExceededPolicy
+    );
+    assert_eq!(
+        errors::ErrorCode::from(77u8),
+        errors::ErrorCode::Unknown(77)
+    );
+
+    // Codes beyond u8 are kept too.
+    for code in [-1, 256, 100_000] {
+        let error_code: errors::ErrorCode = serde_json::from_str(&code.to_string()).unwrap();
+        assert_eq!(error_code, errors::ErrorCode::Unknown(code));
+        assert_eq!(
+            serde_json::to_string(&error_code).unwrap(),
+            code.to_string()
+        );
+    }
+}
+
+#[test]
+fn strict_mode_denies_unknown_fields() {
+    let data = br#"{"pre":[],"active":[],"approved":[],"rejected":[],"abandoned":[],"unreviewed":[],"censored":[],"archived":["abc"]}"#;
+
+    match strict::from_slice_strict::<types::TokenInventory>(data) {
+        Err(StrictError::UnknownFields(fields)) => assert_eq!(fields, vec!["archived"]),
+        other => panic!("expected unknown fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn drift_reports_renamed_fields() {
+    let mut proposals: serde_json::Value =
+        serde_json::from_slice(&read("batchproposals.json")).unwrap();
+    proposals["proposals"].as_array_mut().unwrap().truncate(1);
+    let proposal = proposals["proposals"][0].as_object_mut().unwrap();
+    let record = proposal.remove("censorshiprecord").unwrap();
+    proposal.insert("censorship_record".to_string(), record);
+
+    let data = serde_json::to_vec(&proposals).unwrap();
+    let (_, drift) = strict::from_slice_with_drift::<types::ProposalsResult>(&data).unwrap();
+
+    assert_eq!(
+        drift,
+        Drift {
+            unknown: vec!["proposals[0].censorship_record".to_string()],
+            missing: vec![
+                "proposals[0].abandonedat".to_string(),
+                "proposals[0].censoredat".to_string(),
+                "proposals[0].censorshiprecord".to_string(),
+                "proposals[0].linkto".to_string(),
+                "proposals[0].statuschangemessage".to_string(),
+            ],
+        }
+    );
+}
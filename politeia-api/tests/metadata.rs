@@ -0,0 +1,93 @@
+use politeia_api::v1::{
+    metadata::{MetadataError, MetadataPayload, HINT_PROPOSAL_METADATA, HINT_STATUS_CHANGE},
+    types,
+};
+use sha2::{Digest, Sha256};
+
+fn metadata(hint: &str, payload: &[u8]) -> types::Metadata {
+    types::Metadata {
+        digest: hex::encode(Sha256::digest(payload)),
+        hint: hint.to_string(),
+        payload: base64::encode(payload),
+    }
+}
+
+#[test]
+fn decode_refuses_digest_mismatches() {
+    let mut tampered = metadata(HINT_PROPOSAL_METADATA, br#"{"name":"Proposal"}"#);
+    tampered.payload = base64::encode(br#"{"name":"Tampered"}"#);
+
+    match tampered.decode() {
+        Err(MetadataError::DigestMismatch {
+            hint,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(hint, HINT_PROPOSAL_METADATA);
+            assert_eq!(expected, tampered.digest);
+            assert_eq!(
+                actual,
+                hex::encode(Sha256::digest(br#"{"name":"Tampered"}"#))
+            );
+        }
+        e => panic!("expected a digest mismatch, got {:?}", e),
+    }
+
+    let proposal = types::Proposal {
+        metadata: vec![tampered],
+        ..Default::default()
+    };
+    assert!(proposal.proposal_metadata().is_err());
+    assert!(proposal.decoded_metadata().is_err());
+}
+
+#[test]
+fn decode_keeps_unknown_hints_raw() {
+    let payload = br#"{"anything":true}"#;
+
+    assert_eq!(
+        metadata("votemetadata", payload).decode().unwrap(),
+        MetadataPayload::Raw {
+            hint: "votemetadata".to_string(),
+            payload: payload.to_vec(),
+        }
+    );
+
+    // Raw payloads are not parsed, they need not be JSON.
+    assert_eq!(
+        metadata("binary", &[0, 159, 255]).decode().unwrap(),
+        MetadataPayload::Raw {
+            hint: "binary".to_string(),
+            payload: vec![0, 159, 255],
+        }
+    );
+}
+
+#[test]
+fn decode_parses_known_hints() {
+    let proposal = types::Proposal {
+        metadata: vec![
+            metadata(HINT_PROPOSAL_METADATA, br#"{"name":"Proposal","linkby":5}"#),
+            metadata(HINT_STATUS_CHANGE, br#"{"status":4,"timestamp":20}"#),
+            metadata(HINT_STATUS_CHANGE, br#"{"status":2,"timestamp":10}"#),
+            metadata("votemetadata", b"{}"),
+        ],
+        ..Default::default()
+    };
+
+    let proposal_metadata = proposal.proposal_metadata().unwrap().unwrap();
+    assert_eq!(proposal_metadata.name, "Proposal");
+    assert_eq!(proposal_metadata.link_by, 5);
+
+    let timestamps: Vec<i64> = proposal
+        .status_changes()
+        .unwrap()
+        .iter()
+        .map(|change| change.timestamp)
+        .collect();
+    assert_eq!(timestamps, vec![10, 20]);
+    assert_eq!(proposal.decoded_metadata().unwrap().len(), 4);
+
+    let invalid = metadata(HINT_STATUS_CHANGE, b"not json");
+    assert!(matches!(invalid.decode(), Err(MetadataError::Json(_))));
+}
@@ -0,0 +1,110 @@
+use politeia_api::v1::{
+    types,
+    verify::{merkle_root, VerifyError},
+};
+use sha2::{Digest, Sha256};
+
+fn read<T: serde::de::DeserializeOwned>(name: &str) -> T {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let data = std::fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path, e));
+    serde_json::from_slice(&data).unwrap_or_else(|e| panic!("decoding {}: {}", path, e))
+}
+
+fn proposal() -> (types::Proposal, String) {
+    let policy: types::Policy = read("policy.json");
+    let mut result: types::ProposalsResult = read("batchproposals.json");
+
+    (result.proposals.remove(0), policy.backend_public_key)
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut out = [0; 32];
+    out.copy_from_slice(&Sha256::digest(data));
+    out
+}
+
+fn concat(left: &[u8; 32], right: &[u8; 32]) -> Vec<u8> {
+    [&left[..], &right[..]].concat()
+}
+
+#[test]
+fn merkle_root_duplicates_odd_nodes() {
+    let (a, b, c) = (sha256(b"a"), sha256(b"b"), sha256(b"c"));
+
+    assert_eq!(merkle_root(&[]), [0; 32]);
+    assert_eq!(merkle_root(&[a]), a);
+    assert_eq!(merkle_root(&[a, b]), sha256(&concat(&a, &b)));
+
+    let ab = sha256(&concat(&a, &b));
+    let cc = sha256(&concat(&c, &c));
+    assert_eq!(merkle_root(&[a, b, c]), sha256(&concat(&ab, &cc)));
+}
+
+#[test]
+fn merkle_root_covers_files_then_metadata() {
+    let (proposal, _) = proposal();
+
+    let file = hex::decode(&proposal.files[0].digest).unwrap();
+    let metadata = hex::decode(&proposal.metadata[0].digest).unwrap();
+    let expected = hex::encode(Sha256::digest(&[file, metadata].concat()));
+
+    assert_eq!(proposal.merkle_root().unwrap(), expected);
+}
+
+#[test]
+fn wrong_merkle_root_is_refused() {
+    let (mut proposal, key) = proposal();
+    proposal.censorship_record.merkle = hex::encode(sha256(b"other merkle root"));
+
+    assert!(matches!(
+        proposal.verify(&key),
+        Err(VerifyError::MerkleMismatch { .. })
+    ));
+}
+
+#[test]
+fn bad_signature_is_refused() {
+    let (mut proposal, key) = proposal();
+    let other: types::ProposalsResult = read("batchproposals.json");
+    // A valid signature over another record.
+    proposal.censorship_record.signature = other.proposals[1].censorship_record.signature.clone();
+
+    assert!(matches!(
+        proposal.verify(&key),
+        Err(VerifyError::Signature(_))
+    ));
+
+    proposal.censorship_record.signature = "00".repeat(64);
+    assert!(matches!(
+        proposal.verify(&key),
+        Err(VerifyError::Signature(_))
+    ));
+}
+
+#[test]
+fn signature_covers_the_token() {
+    let (mut proposal, key) = proposal();
+    proposal.censorship_record.token = hex::encode(sha256(b"another token"));
+
+    assert!(matches!(
+        proposal.verify(&key),
+        Err(VerifyError::Signature(_))
+    ));
+}
+
+#[test]
+fn wrong_public_key_is_refused() {
+    let (proposal, _) = proposal();
+    // Public key of the mock politeiawww, a valid key that did not sign.
+    let other_key = "c081ea117035493b19091444e4d220c43a0956635bc4b030b720e24deb196cc5";
+
+    assert!(matches!(
+        proposal.verify(other_key),
+        Err(VerifyError::Signature(_))
+    ));
+
+    assert!(matches!(
+        proposal.verify("not hex"),
+        Err(VerifyError::Hex(_))
+    ));
+}
@@ -0,0 +1,234 @@
+use super::{events::EventBus, rfp::RfpIndex, search::SearchIndex};
+use politeia_api::v1::types;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::Arc,
+};
+use tokio::sync::RwLock;
+
+/// Politeia data mirrored by the updater and served by the HTTP server.
+#[derive(Clone, Default)]
+pub struct Cache {
+    pub tokens: Arc<RwLock<types::TokenInventory>>,
+    pub policy: Arc<RwLock<types::Policy>>,
+    pub proposals: Arc<RwLock<HashMap<String, types::Proposal>>>,
+    pub vote_summaries: Arc<RwLock<types::BatchVoteSummaryResult>>,
+    pub stats: Arc<RwLock<CacheStats>>,
+    pub search: Arc<RwLock<SearchIndex>>,
+    pub versions: Arc<RwLock<VersionHistory>>,
+    /// Prior versions that failed to fetch or verify, not snapshotted.
+    pub version_failures: Arc<RwLock<HashMap<String, BTreeMap<u32, FetchFailure>>>>,
+    pub comments: Arc<RwLock<HashMap<String, CachedComments>>>,
+    /// Proposals whose comments failed to fetch, not snapshotted.
+    pub comment_failures: Arc<RwLock<HashMap<String, FetchFailure>>>,
+    pub rfps: Arc<RwLock<RfpIndex>>,
+    pub events: EventBus,
+}
+
+/// Comments of a proposal along with the proposal comment count they were
+/// fetched at, a differing count marks them stale.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct CachedComments {
+    pub number_of_comments: usize,
+    pub comments: Vec<types::Comment>,
+}
+
+/// Superseded versions of a proposal keyed by token, then by version number.
+/// The latest version lives in [Cache::proposals].
+pub type VersionHistory = HashMap<String, BTreeMap<u32, types::Proposal>>;
+
+/// Data that failed to fetch or verify, it is not fetched again before
+/// `retry_at`.
+#[derive(Debug, Clone)]
+pub struct FetchFailure {
+    pub failures: u32,
+    pub retry_at: std::time::Instant,
+}
+
+/// Counters describing the updater activity since the server started.
+#[derive(serde::Serialize, Default, Debug, Clone)]
+pub struct CacheStats {
+    /// UNIX timestamp of the last successful refresh.
+    pub last_refresh: u64,
+    /// Number of successful refreshes.
+    pub refreshes: u64,
+    /// Proposals fetched during the last refresh.
+    pub last_fetched: usize,
+    /// Tokens that failed to fetch during the last refresh.
+    pub last_failed: usize,
+    /// Proposals refused during the last refresh for failing verification.
+    pub last_refused: usize,
+    /// Proposals evicted during the last refresh.
+    pub last_evicted: usize,
+    /// Proposals evicted since the server started.
+    pub total_evicted: usize,
+}
+
+impl CacheStats {
+    pub fn record_refresh(
+        &mut self,
+        fetched: usize,
+        failed: usize,
+        refused: usize,
+        evicted: usize,
+    ) {
+        self.last_refresh = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.refreshes += 1;
+        self.last_fetched = fetched;
+        self.last_failed = failed;
+        self.last_refused = refused;
+        self.last_evicted = evicted;
+        self.total_evicted += evicted;
+    }
+}
+
+/// Token prefix length used when the policy has not been fetched yet.
+const DEFAULT_TOKEN_PREFIX_LENGTH: usize = 7;
+
+/// Errors resolving a token prefix to a cached proposal token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// Prefix is shorter than the policy token prefix length.
+    TooShort(usize),
+    /// No cached proposal matches the prefix.
+    NotFound,
+    /// Several cached proposals match the prefix.
+    Ambiguous(Vec<String>),
+}
+
+impl std::fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolveError::TooShort(min) => {
+                write!(f, "token prefix must be at least {} characters", min)
+            }
+            ResolveError::NotFound => write!(f, "no proposal matches token"),
+            ResolveError::Ambiguous(tokens) => {
+                write!(f, "token prefix matches {} proposals", tokens.len())
+            }
+        }
+    }
+}
+
+/// Returns the given version of a cached proposal, the latest version from
+/// `proposals` and superseded ones from `versions`.
+pub fn proposal_version<'a>(
+    proposals: &'a HashMap<String, types::Proposal>,
+    versions: &'a VersionHistory,
+    token: &str,
+    version: u32,
+) -> Option<&'a types::Proposal> {
+    match proposals.get(token) {
+        Some(proposal) if proposal.version.parse::<u32>().ok() == Some(version) => Some(proposal),
+
+        _ => versions
+            .get(token)
+            .and_then(|history| history.get(&version)),
+    }
+}
+
+/// Resolves a full censorship token or a token prefix of at least
+/// `prefix_length` characters to the token of a cached proposal.
+pub fn resolve_token(
+    proposals: &HashMap<String, types::Proposal>,
+    token_or_prefix: &str,
+    prefix_length: usize,
+) -> Result<String, ResolveError> {
+    if proposals.contains_key(token_or_prefix) {
+        return Ok(token_or_prefix.to_string());
+    }
+
+    let prefix_length = match prefix_length {
+        0 => DEFAULT_TOKEN_PREFIX_LENGTH,
+        e => e,
+    };
+    if token_or_prefix.len() < prefix_length {
+        return Err(ResolveError::TooShort(prefix_length));
+    }
+
+    let mut matches: Vec<String> = proposals
+        .keys()
+        .filter(|token| token.starts_with(token_or_prefix))
+        .cloned()
+        .collect();
+
+    match matches.len() {
+        0 => Err(ResolveError::NotFound),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            matches.sort();
+            Err(ResolveError::Ambiguous(matches))
+        }
+    }
+}
+
+/// On-disk representation of the [Cache].
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
+struct Snapshot {
+    tokens: types::TokenInventory,
+    policy: types::Policy,
+    proposals: HashMap<String, types::Proposal>,
+    vote_summaries: types::BatchVoteSummaryResult,
+    versions: VersionHistory,
+    comments: HashMap<String, CachedComments>,
+}
+
+impl Cache {
+    /// Loads a cache snapshot from disk, a missing snapshot yields an empty cache.
+    pub async fn load(path: &Path) -> std::io::Result<Cache> {
+        let data = match tokio::fs::read(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Cache::default()),
+            Err(e) => return Err(e),
+        };
+
+        let snapshot: Snapshot = serde_json::from_slice(&data)?;
+        let rfps = super::rfp::build_index(&snapshot.proposals);
+        let search =
+            SearchIndex::build(snapshot.proposals.values(), &snapshot.policy.index_filename);
+
+        Ok(Cache {
+            tokens: Arc::new(RwLock::new(snapshot.tokens)),
+            policy: Arc::new(RwLock::new(snapshot.policy)),
+            proposals: Arc::new(RwLock::new(snapshot.proposals)),
+            vote_summaries: Arc::new(RwLock::new(snapshot.vote_summaries)),
+            stats: Arc::default(),
+            search: Arc::new(RwLock::new(search)),
+            rfps: Arc::new(RwLock::new(rfps)),
+            versions: Arc::new(RwLock::new(snapshot.versions)),
+            version_failures: Arc::default(),
+            comments: Arc::new(RwLock::new(snapshot.comments)),
+            comment_failures: Arc::default(),
+            events: EventBus::default(),
+        })
+    }
+
+    /// Writes a snapshot of the cache to disk.
+    ///
+    /// The snapshot is written to a temporary file first and renamed over the
+    /// previous one so a crash mid-write never leaves a truncated snapshot.
+    pub async fn save(&self, path: &Path) -> std::io::Result<()> {
+        let snapshot = Snapshot {
+            tokens: self.tokens.read().await.clone(),
+            policy: self.policy.read().await.clone(),
+            proposals: self.proposals.read().await.clone(),
+            vote_summaries: self.vote_summaries.read().await.clone(),
+            versions: self.versions.read().await.clone(),
+            comments: self.comments.read().await.clone(),
+        };
+
+        let data = serde_json::to_vec(&snapshot)?;
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+
+        tokio::fs::write(&tmp_path, data).await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    io::{Error, ErrorKind},
+    path::PathBuf,
+};
+
+/// Environment variable holding the port the server listens on.
+const ENV_PORT: &str = "PORT";
+/// Environment variable holding the politeiawww API host to mirror.
+const ENV_POLITEIA_HOST: &str = "POLITEIA_HOST";
+/// Environment variable holding the path of the cache snapshot file.
+const ENV_CACHE_PATH: &str = "POLITEIA_CACHE_PATH";
+/// Environment variable holding comma separated webhook target urls.
+const ENV_WEBHOOKS: &str = "POLITEIA_WEBHOOKS";
+/// Environment variable holding the secret webhook payloads are signed with.
+const ENV_WEBHOOK_SECRET: &str = "POLITEIA_WEBHOOK_SECRET";
+
+const DEFAULT_CACHE_PATH: &str = "politeia_cache.json";
+
+/// Server configuration, read from environment variables and overridden by
+/// command line flags.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Port the HTTP server binds to.
+    pub port: String,
+    /// Base url of the politeiawww API the cache is populated from.
+    pub politeia_host: String,
+    /// File the cache is snapshotted to, persistence is disabled when `None`.
+    pub cache_path: Option<PathBuf>,
+    /// Urls lifecycle events are posted to.
+    pub webhooks: Vec<String>,
+    /// Secret webhook payloads are signed with, required when webhooks are
+    /// configured.
+    pub webhook_secret: Option<String>,
+}
+
+impl Config {
+    /// Reads the configuration from the process environment and arguments.
+    ///
+    /// Supported flags:
+    ///   * `--port <port>`
+    ///   * `--host <url>` politeiawww API base url.
+    ///   * `--testnet` use the testnet politeiawww host, conflicts with
+    ///     `--host`.
+    ///   * `--cache <path>` cache snapshot file.
+    ///   * `--no-cache` disable cache persistence.
+    ///   * `--webhook <url>` add a webhook target, may be repeated.
+    ///   * `--webhook-secret <secret>` webhook payload signing secret,
+    ///     required along with webhook targets.
+    pub fn from_env() -> std::io::Result<Config> {
+        let mut config = Config {
+            port: std::env::var(ENV_PORT).unwrap_or_default(),
+            politeia_host: std::env::var(ENV_POLITEIA_HOST)
+                .unwrap_or_else(|_| politeia_api::POLITEIA_HOST.to_string()),
+            cache_path: cache_path(
+                std::env::var(ENV_CACHE_PATH).unwrap_or_else(|_| DEFAULT_CACHE_PATH.into()),
+            ),
+            webhooks: webhooks(&std::env::var(ENV_WEBHOOKS).unwrap_or_default()),
+            webhook_secret: std::env::var(ENV_WEBHOOK_SECRET)
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+        };
+
+        let (mut host, mut testnet) = (false, false);
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--port" => config.port = flag_value(&arg, args.next())?,
+                "--host" => {
+                    config.politeia_host = flag_value(&arg, args.next())?;
+                    host = true;
+                }
+                "--cache" => config.cache_path = cache_path(flag_value(&arg, args.next())?),
+                "--no-cache" => config.cache_path = None,
+                "--webhook" => config.webhooks.push(flag_value(&arg, args.next())?),
+                "--webhook-secret" => config.webhook_secret = Some(flag_value(&arg, args.next())?),
+                "--testnet" => {
+                    config.politeia_host = politeia_api::TESTNET_POLITEIA_HOST.into();
+                    testnet = true;
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("unknown argument {}", arg),
+                    ))
+                }
+            }
+        }
+
+        if host && testnet {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--host and --testnet cannot be used together",
+            ));
+        }
+
+        if config.port.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Port env not set."));
+        }
+
+        if config.politeia_host.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Politeia host is an empty string",
+            ));
+        }
+
+        if !config.webhooks.is_empty() && config.webhook_secret.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Webhooks require a signing secret, set --webhook-secret",
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+fn flag_value(flag: &str, value: Option<String>) -> std::io::Result<String> {
+    value.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("missing value for {}", flag),
+        )
+    })
+}
+
+/// An empty path disables cache persistence.
+fn cache_path(path: String) -> Option<PathBuf> {
+    if path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Splits a comma separated list of webhook urls, skipping empty entries.
+fn webhooks(urls: &str) -> Vec<String> {
+    urls.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
@@ -0,0 +1,163 @@
+use super::types::FileSummary;
+use politeia_api::v1::types::{File, Proposal};
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged lines of context kept around each hunk of the index diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// A value that changed between two proposal versions.
+#[derive(serde::Serialize, Debug)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// A file present in both versions whose digest changed.
+#[derive(serde::Serialize, Debug)]
+pub struct FileChange {
+    pub name: String,
+    pub from_digest: String,
+    pub to_digest: String,
+}
+
+/// Files added, removed or modified between two proposal versions.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct FilesDiff {
+    pub added: Vec<FileSummary>,
+    pub removed: Vec<FileSummary>,
+    pub modified: Vec<FileChange>,
+}
+
+/// A single line of a diff hunk.
+#[derive(serde::Serialize, Debug)]
+pub struct DiffLine {
+    /// One of `equal`, `insert` or `delete`.
+    pub op: &'static str,
+    pub text: String,
+}
+
+/// A group of changed lines along with surrounding context.
+#[derive(serde::Serialize, Debug)]
+pub struct Hunk {
+    /// First line of the hunk in the old version, starting at 1.
+    pub old_start: usize,
+    pub old_lines: usize,
+    /// First line of the hunk in the new version, starting at 1.
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Structured diff between two versions of a proposal.
+#[derive(serde::Serialize, Debug)]
+pub struct ProposalDiff {
+    pub token: String,
+    pub from: u32,
+    pub to: u32,
+    /// Set when the proposal name changed.
+    pub name: Option<Change<String>>,
+    pub files: FilesDiff,
+    /// Line diff of the index file, `None` if either version could not be
+    /// decoded.
+    pub index: Option<Vec<Hunk>>,
+}
+
+impl ProposalDiff {
+    /// Diffs version `from` of a proposal against version `to`.
+    pub fn new(
+        old: &Proposal,
+        new: &Proposal,
+        from: u32,
+        to: u32,
+        index_filename: &str,
+    ) -> ProposalDiff {
+        let name = if old.name != new.name {
+            Some(Change {
+                from: old.name.clone(),
+                to: new.name.clone(),
+            })
+        } else {
+            None
+        };
+
+        let index = match (
+            old.index_markdown(index_filename),
+            new.index_markdown(index_filename),
+        ) {
+            (Ok(old_index), Ok(new_index)) => Some(diff_lines(&old_index, &new_index)),
+            _ => None,
+        };
+
+        ProposalDiff {
+            token: new.censorship_record.token.clone(),
+            from,
+            to,
+            name,
+            files: diff_files(&old.files, &new.files),
+            index,
+        }
+    }
+}
+
+fn diff_files(old: &[File], new: &[File]) -> FilesDiff {
+    let mut diff = FilesDiff::default();
+
+    for file in new {
+        match old.iter().find(|f| f.name == file.name) {
+            Some(previous) if previous.digest != file.digest => {
+                diff.modified.push(FileChange {
+                    name: file.name.clone(),
+                    from_digest: previous.digest.clone(),
+                    to_digest: file.digest.clone(),
+                });
+            }
+
+            Some(_) => {}
+
+            None => diff.added.push(FileSummary::from(file)),
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|file| !new.iter().any(|f| f.name == file.name))
+        .map(FileSummary::from)
+        .collect();
+
+    diff
+}
+
+fn diff_lines(old: &str, new: &str) -> Vec<Hunk> {
+    let diff = TextDiff::from_lines(old, new);
+
+    diff.grouped_ops(DIFF_CONTEXT_LINES)
+        .iter()
+        .map(|group| {
+            let first = &group[0];
+            let last = &group[group.len() - 1];
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    op: match change.tag() {
+                        ChangeTag::Equal => "equal",
+                        ChangeTag::Insert => "insert",
+                        ChangeTag::Delete => "delete",
+                    },
+                    text: change.value().trim_end_matches('\n').to_string(),
+                })
+                .collect();
+
+            Hunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            }
+        })
+        .collect()
+}
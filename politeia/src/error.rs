@@ -0,0 +1,115 @@
+use politeia_api::v1::errors::ErrorCode;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Errors returned by [Client](super::model::Client) requests.
+#[derive(Debug)]
+pub enum ClientError {
+    /// Transport level failure, e.g connection refused or timed out.
+    Network(reqwest::Error),
+    /// Politeiawww rejected the request with a user error.
+    Api {
+        status: StatusCode,
+        code: ErrorCode,
+        context: Vec<String>,
+    },
+    /// Non-2xx response that did not carry a politeiawww user error.
+    Status(StatusCode),
+    /// Server asked the client to slow down with a 429 or 503 response.
+    RateLimited {
+        status: StatusCode,
+        /// Delay requested by the `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+    /// CSRF token could not be retrieved from the server.
+    Csrf(String),
+    /// Request or response body could not be (de)serialized.
+    Json(serde_json::Error),
+}
+
+impl ClientError {
+    /// Returns the politeiawww error code if the server returned one.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Api { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status of the failed response, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api { status, .. }
+            | ClientError::Status(status)
+            | ClientError::RateLimited { status, .. } => Some(*status),
+            ClientError::Network(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// Reports if retrying the same request may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Network(e) => !e.is_builder(),
+            ClientError::Status(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            ClientError::RateLimited { .. } | ClientError::Csrf(_) => true,
+            ClientError::Api { .. } | ClientError::Json(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Network(e) => write!(f, "network error: {}", e),
+            ClientError::Api {
+                status,
+                code,
+                context,
+            } => {
+                write!(f, "politeia error {}: {}", status, code)?;
+                if !context.is_empty() {
+                    write!(f, " ({})", context.join(", "))?;
+                }
+                Ok(())
+            }
+            ClientError::Status(status) => write!(f, "unexpected response status {}", status),
+            ClientError::RateLimited {
+                status,
+                retry_after,
+            } => {
+                write!(f, "rate limited with status {}", status)?;
+                if let Some(retry_after) = retry_after {
+                    write!(f, ", retry after {}s", retry_after.as_secs())?;
+                }
+                Ok(())
+            }
+            ClientError::Csrf(e) => write!(f, "csrf error: {}", e),
+            ClientError::Json(e) => write!(f, "json error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Network(e) => Some(e),
+            ClientError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Network(e)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        ClientError::Json(e)
+    }
+}
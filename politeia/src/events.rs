@@ -0,0 +1,169 @@
+use politeia_api::v1::{
+    enum_values::ProposalVoteStatus,
+    types::{Proposal, TokenInventory, VoteSummary},
+};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// Events kept for subscribers that fall behind before they are dropped.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Change to the mirrored politeia data detected by the updater.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    /// A token appeared in the inventory.
+    NewProposal {
+        token: String,
+        name: String,
+        bucket: String,
+    },
+    /// A token moved between inventory buckets.
+    StatusChange {
+        token: String,
+        from: String,
+        to: String,
+    },
+    /// A proposal was edited.
+    NewVersion { token: String, version: String },
+    /// Voting on a proposal started.
+    VoteStarted { token: String, end_height: u64 },
+    /// Voting on a proposal finished.
+    VoteFinished { token: String, approved: bool },
+}
+
+impl ChangeEvent {
+    /// Name of the event, used as the server-sent event type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeEvent::NewProposal { .. } => "new_proposal",
+            ChangeEvent::StatusChange { .. } => "status_change",
+            ChangeEvent::NewVersion { .. } => "new_version",
+            ChangeEvent::VoteStarted { .. } => "vote_started",
+            ChangeEvent::VoteFinished { .. } => "vote_finished",
+        }
+    }
+
+    /// Token of the proposal the event is about.
+    pub fn token(&self) -> &str {
+        match self {
+            ChangeEvent::NewProposal { token, .. }
+            | ChangeEvent::StatusChange { token, .. }
+            | ChangeEvent::NewVersion { token, .. }
+            | ChangeEvent::VoteStarted { token, .. }
+            | ChangeEvent::VoteFinished { token, .. } => token,
+        }
+    }
+}
+
+/// Broadcast channel the updater publishes change events on.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ChangeEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        EventBus { sender }
+    }
+}
+
+impl EventBus {
+    /// Sends events to the current subscribers, events are dropped when
+    /// nobody is subscribed.
+    pub fn publish(&self, events: Vec<ChangeEvent>) {
+        for event in events {
+            log::debug!("Publishing {} event", event.name());
+            if self.sender.send(event).is_err() {
+                break;
+            }
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// New proposals and bucket moves between two token inventories.
+pub fn inventory_events(
+    old: &TokenInventory,
+    new: &TokenInventory,
+    proposals: &HashMap<String, Proposal>,
+) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+
+    for (bucket, tokens) in new.buckets() {
+        for token in tokens {
+            match old.bucket_of(token) {
+                Some(from) if from == bucket => {}
+
+                Some(from) => events.push(ChangeEvent::StatusChange {
+                    token: token.clone(),
+                    from: from.to_string(),
+                    to: bucket.to_string(),
+                }),
+
+                None => events.push(ChangeEvent::NewProposal {
+                    token: token.clone(),
+                    name: proposals
+                        .get(token)
+                        .map(|proposal| proposal.name.clone())
+                        .unwrap_or_default(),
+                    bucket: bucket.to_string(),
+                }),
+            }
+        }
+    }
+
+    events
+}
+
+/// Proposals whose version differs from the version previously cached.
+pub fn version_events(
+    old_versions: &HashMap<String, String>,
+    proposals: &HashMap<String, Proposal>,
+) -> Vec<ChangeEvent> {
+    proposals
+        .iter()
+        .filter_map(|(token, proposal)| match old_versions.get(token) {
+            Some(version) if *version != proposal.version => Some(ChangeEvent::NewVersion {
+                token: token.clone(),
+                version: proposal.version.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Votes that started or finished between two sets of vote summaries.
+pub fn vote_events(
+    old: &HashMap<String, VoteSummary>,
+    new: &HashMap<String, VoteSummary>,
+) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+
+    for (token, summary) in new {
+        let old_status = old.get(token).map(|summary| summary.status);
+        if old_status == Some(summary.status) {
+            continue;
+        }
+
+        match summary.status {
+            ProposalVoteStatus::Started => events.push(ChangeEvent::VoteStarted {
+                token: token.clone(),
+                end_height: summary.end_height,
+            }),
+
+            ProposalVoteStatus::Finished => events.push(ChangeEvent::VoteFinished {
+                token: token.clone(),
+                approved: summary.approved,
+            }),
+
+            _ => {}
+        }
+    }
+
+    events
+}
@@ -0,0 +1,13 @@
+pub mod cache;
+pub mod config;
+pub mod diff;
+pub mod error;
+pub mod events;
+pub mod model;
+pub mod render;
+pub mod retry;
+pub mod rfp;
+pub mod search;
+pub mod server;
+pub mod types;
+pub mod webhook;
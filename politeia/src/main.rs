@@ -1,9 +1,9 @@
-pub mod model;
-mod server;
-mod types;
+use politeia::{config::Config, server};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     pretty_env_logger::init();
-    server::start_server().await
+
+    let config = Config::from_env()?;
+    server::start_server(config).await
 }
@@ -1,22 +1,96 @@
+use super::{
+    cache::{Cache, CachedComments, FetchFailure},
+    error::ClientError,
+    events,
+    retry::{self, RetryPolicy},
+};
+use futures::{future, Future};
 use politeia_api as api;
-use std::{collections::HashMap, sync::Arc};
-use tokio::{sync::RwLock, time};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::{
+    sync::{Mutex, RwLock, Semaphore},
+    time,
+};
 
 pub struct Client {
     client: reqwest::Client,
+    host: String,
+    /// Bounds the number of requests in flight.
+    requests: Semaphore,
+    retry: RetryPolicy,
 
-    csrf_token: String,
-    csrf_expiry: std::time::Instant,
+    csrf: Mutex<Csrf>,
 
     pub policy: api::v1::types::Policy,
 }
 
+/// CSRF token POST requests are signed with.
+struct Csrf {
+    token: String,
+    renewed_at: std::time::Instant,
+}
+
 const CSRF_TOKEN: &str = "X-CSRF-Token";
 const MAX_CSRF_EXPIRY_SECS: u64 = 20 * 60 * 60;
 const MAX_TIME_CACHE_UPDATE_SECS: u64 = 10 * 60;
+/// Prior proposal versions fetched at most per refresh.
+const MAX_VERSION_FETCHES_PER_REFRESH: usize = 50;
+/// Delay before refetching a prior version that failed, doubled after each
+/// failure.
+const VERSION_RETRY_DELAY_SECS: u64 = 60 * 60;
+const MAX_VERSION_RETRY_DELAY_SECS: u64 = 7 * 24 * 60 * 60;
+/// Delay before refetching comments that failed, doubled after each failure.
+const COMMENT_RETRY_DELAY_SECS: u64 = 5 * 60;
+const MAX_COMMENT_RETRY_DELAY_SECS: u64 = 24 * 60 * 60;
+/// Requests in flight at once by default.
+const DEFAULT_CONCURRENCY: usize = 4;
 
-impl Client {
-    pub fn new() -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
+/// Builds a [Client] for a given politeiawww host.
+pub struct ClientBuilder {
+    host: String,
+    concurrency: usize,
+    retry: RetryPolicy,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder {
+            host: api::POLITEIA_HOST.to_string(),
+            concurrency: DEFAULT_CONCURRENCY,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Sets the politeiawww API base url, e.g `https://proposals.decred.org/api`.
+    /// Defaults to mainnet.
+    pub fn host<S: Into<String>>(mut self, host: S) -> ClientBuilder {
+        self.host = host.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the number of requests sent concurrently, at least one.
+    pub fn concurrency(mut self, concurrency: usize) -> ClientBuilder {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets how failed requests are retried, defaults to
+    /// [RetryPolicy::default].
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> ClientBuilder {
+        self.retry = retry;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, ClientError> {
         let req_client = reqwest::ClientBuilder::default()
             .connection_verbose(true)
             .cookie_store(true)
@@ -24,83 +98,161 @@ impl Client {
 
         let client = Client {
             client: req_client,
-            csrf_token: String::new(),
-            csrf_expiry: std::time::Instant::now(),
+            host: self.host,
+            requests: Semaphore::new(self.concurrency),
+            retry: self.retry,
+            csrf: Mutex::new(Csrf {
+                token: String::new(),
+                renewed_at: std::time::Instant::now(),
+            }),
             policy: api::v1::types::Policy::default(),
         };
 
         Ok(client)
     }
+}
 
-    async fn get_request(
+impl Client {
+    /// Creates a client against the mainnet politeiawww host.
+    pub fn new() -> Result<Client, ClientError> {
+        ClientBuilder::default().build()
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Returns the politeiawww API base url the client talks to.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    fn url(&self, route: &str) -> String {
+        format!("{}{}", self.host, route)
+    }
+
+    /// Sends requests until one succeeds or the retry policy gives up.
+    async fn with_retry<F, Fut>(
         &self,
-        url: String,
-    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-        let response = self.client.get(&url).send().await?;
-        match response.error_for_status() {
-            Ok(res) => Ok(res.bytes().await?.to_vec()),
+        idempotent: bool,
+        mut request: F,
+    ) -> Result<Vec<u8>, ClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Vec<u8>, ClientError>>,
+    {
+        let mut attempt = 0;
 
-            Err(e) => Err(e.into()),
+        loop {
+            let e = match request().await {
+                Ok(e) => return Ok(e),
+                Err(e) => e,
+            };
+
+            attempt += 1;
+            match self.retry.retry_delay(attempt, &e, idempotent) {
+                Some(delay) => {
+                    log::warn!("Retrying request in {}ms, error: {}", delay.as_millis(), e);
+                    time::delay_for(delay).await;
+                }
+
+                None => return Err(e),
+            }
         }
     }
 
+    async fn get_request(&self, url: String) -> Result<Vec<u8>, ClientError> {
+        self.with_retry(true, || async {
+            let _permit = self.requests.acquire().await;
+            let response = self.client.get(&url).send().await?;
+            read_response(response).await
+        })
+        .await
+    }
+
+    /// Sends a POST request, `idempotent` requests are retried on any
+    /// transient error.
     async fn post_request(
-        &mut self,
+        &self,
         url: String,
         params: Vec<u8>,
-    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-        if self.csrf_token == ""
-            || self.csrf_expiry.elapsed() > std::time::Duration::from_secs(MAX_CSRF_EXPIRY_SECS)
-        {
-            log::info!("Updating csrf.");
-            self.version().await?;
-            self.policy = self.fetch_policy().await?;
-            self.csrf_expiry = std::time::Instant::now();
+        idempotent: bool,
+    ) -> Result<Vec<u8>, ClientError> {
+        self.with_retry(idempotent, || self.post_once(&url, &params))
+            .await
+    }
+
+    async fn post_once(&self, url: &str, params: &[u8]) -> Result<Vec<u8>, ClientError> {
+        let _permit = self.requests.acquire().await;
+
+        let csrf_token = self.csrf_token(None).await?;
+        let response = self.send_post(url, &csrf_token, params.to_vec()).await?;
+
+        // Politeiawww forgets CSRF tokens on restart, renew the token once
+        // before giving up.
+        if response.status() == reqwest::StatusCode::FORBIDDEN {
+            log::warn!("Csrf token rejected.");
+            let csrf_token = self.csrf_token(Some(&csrf_token)).await?;
+
+            let response = self.send_post(url, &csrf_token, params.to_vec()).await?;
+            return read_response(response).await;
         }
 
+        read_response(response).await
+    }
+
+    async fn send_post(
+        &self,
+        url: &str,
+        csrf_token: &str,
+        params: Vec<u8>,
+    ) -> Result<reqwest::Response, ClientError> {
         let response = self
             .client
-            .post(&url)
-            .header(CSRF_TOKEN, &self.csrf_token)
+            .post(url)
+            .header(CSRF_TOKEN, csrf_token)
             .body(params)
             .send()
             .await?;
 
-        match response.error_for_status() {
-            Ok(res) => Ok(res.bytes().await?.to_vec()),
+        Ok(response)
+    }
 
-            Err(e) => Err(e.into()),
+    /// Returns the CSRF token, renewing it when unset, expired or equal to
+    /// the `rejected` token. Concurrent callers wait for a single renewal.
+    async fn csrf_token(&self, rejected: Option<&str>) -> Result<String, ClientError> {
+        let mut csrf = self.csrf.lock().await;
+
+        if csrf.token.is_empty()
+            || csrf.renewed_at.elapsed() > std::time::Duration::from_secs(MAX_CSRF_EXPIRY_SECS)
+            || rejected == Some(csrf.token.as_str())
+        {
+            log::info!("Updating csrf.");
+            csrf.token = self.version().await?;
+            csrf.renewed_at = std::time::Instant::now();
         }
+
+        Ok(csrf.token.clone())
     }
 
-    async fn version(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!(
-            "{}{}",
-            api::POLITEIA_HOST,
-            api::v1::routes::REQUEST_GET_VERSION
-        );
+    /// Fetches the server version, returning the CSRF token it hands out.
+    async fn version(&self) -> Result<String, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_GET_VERSION);
 
         let response = self.client.get(&url).send().await?;
 
         match response.headers().get(CSRF_TOKEN) {
-            Some(e) => {
-                self.csrf_token = e.to_str()?.to_string();
-            }
+            Some(e) => Ok(e
+                .to_str()
+                .map_err(|e| ClientError::Csrf(e.to_string()))?
+                .to_string()),
 
-            None => return Err("Error retrieving CSRF-Token".into()),
+            None => Err(ClientError::Csrf("Error retrieving CSRF-Token".into())),
         }
-
-        Ok(())
     }
 
-    pub async fn fetch_policy(
-        &self,
-    ) -> Result<api::v1::types::Policy, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!(
-            "{}{}",
-            api::POLITEIA_HOST,
-            api::v1::routes::REQUEST_GET_POLICY
-        );
+    pub async fn fetch_policy(&self) -> Result<api::v1::types::Policy, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_GET_POLICY);
 
         let response = self.get_request(url).await?;
         let policy: api::v1::types::Policy = serde_json::from_slice(&response)?;
@@ -109,13 +261,8 @@ impl Client {
 
     pub async fn fetch_tokens(
         &self,
-    ) -> Result<politeia_api::v1::types::TokenInventory, Box<dyn std::error::Error + Send + Sync>>
-    {
-        let url = format!(
-            "{}{}",
-            api::POLITEIA_HOST,
-            api::v1::routes::REQUEST_GET_TOKEN_INVENTORY
-        );
+    ) -> Result<politeia_api::v1::types::TokenInventory, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_GET_TOKEN_INVENTORY);
 
         let response = self.get_request(url).await?;
         let tokens: api::v1::types::TokenInventory = serde_json::from_slice(&response)?;
@@ -123,140 +270,856 @@ impl Client {
     }
 
     pub async fn fetch_batch_proposal(
-        &mut self,
+        &self,
         tokens: Vec<String>,
-    ) -> Result<politeia_api::v1::types::ProposalsResult, Box<dyn std::error::Error + Send + Sync>>
-    {
-        let url = format!(
-            "{}{}",
-            api::POLITEIA_HOST,
-            api::v1::routes::REQUEST_POST_BATCH_PROPOSALS
-        );
+    ) -> Result<politeia_api::v1::types::ProposalsResult, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_POST_BATCH_PROPOSALS);
 
         log::info!("Fetching batch proposal");
 
         let val = serde_json::json!({ "tokens": tokens });
         let params = serde_json::to_vec(&val)?;
 
-        let response = self.post_request(url, params).await?;
+        // Batch routes only read, retrying them is safe.
+        let response = self.post_request(url, params, true).await?;
 
         let policy: api::v1::types::ProposalsResult = serde_json::from_slice(&response)?;
         Ok(policy)
     }
 
+    /// Fetch a single proposal, the latest version is returned when `version` is `None`.
+    pub async fn fetch_proposal_details(
+        &self,
+        token: &str,
+        version: Option<u32>,
+    ) -> Result<api::v1::types::Proposal, ClientError> {
+        let mut url =
+            self.url(&api::v1::routes::REQUEST_GET_PROPOSAL_DETAILS.replace("{token}", token));
+        if let Some(version) = version {
+            url = format!("{}?version={}", url, version);
+        }
+
+        let response = self.get_request(url).await?;
+        let details: api::v1::types::ProposalDetailsResult = serde_json::from_slice(&response)?;
+        Ok(details.proposal)
+    }
+
+    /// Fetch every comment of a proposal.
+    pub async fn fetch_comments(
+        &self,
+        token: &str,
+    ) -> Result<api::v1::types::GetCommentsResult, ClientError> {
+        let url = self.url(&api::v1::routes::REQUEST_GET_COMMENTS.replace("{token}", token));
+
+        let response = self.get_request(url).await?;
+        let comments: api::v1::types::GetCommentsResult = serde_json::from_slice(&response)?;
+        Ok(comments)
+    }
+
+    /// Fetch the vote status of all public proposals.
+    #[allow(deprecated)]
+    pub async fn fetch_vote_status(&self) -> Result<api::v1::types::VoteStatusResult, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_GET_VOTE_STATUS);
+
+        let response = self.get_request(url).await?;
+        let vote_status: api::v1::types::VoteStatusResult = serde_json::from_slice(&response)?;
+        Ok(vote_status)
+    }
+
+    pub async fn fetch_batch_vote_summary(
+        &self,
+        tokens: Vec<String>,
+    ) -> Result<api::v1::types::BatchVoteSummaryResult, ClientError> {
+        let url = self.url(api::v1::routes::REQUEST_POST_BATCH_VOTE_SUMMARY);
+
+        log::info!("Fetching batch vote summary");
+
+        let val = serde_json::json!({ "tokens": tokens });
+        let params = serde_json::to_vec(&val)?;
+
+        // Batch routes only read, retrying them is safe.
+        let response = self.post_request(url, params, true).await?;
+
+        let summaries: api::v1::types::BatchVoteSummaryResult = serde_json::from_slice(&response)?;
+        Ok(summaries)
+    }
+
+    /// Fetch vote summaries of all vetted proposals in the inventory.
+    ///
+    /// A failing batch does not fail the others, the summaries fetched are
+    /// returned along with the tokens of the failed batches.
+    pub async fn fetch_vote_summaries(
+        &mut self,
+        tokens: &api::v1::types::TokenInventory,
+    ) -> Result<VoteSummaryReport, ClientError> {
+        log::info!("Fetching all vote summaries");
+
+        let page_size = self.page_size().await?;
+        let client = &*self;
+
+        let batches = future::join_all(
+            VETTED_BUCKETS
+                .iter()
+                .filter_map(|bucket| Some((*bucket, tokens.bucket(bucket)?)))
+                .flat_map(|(bucket, tokens)| {
+                    tokens.chunks(page_size).map(move |batch| (bucket, batch))
+                })
+                .map(|(bucket, batch)| async move {
+                    (
+                        bucket,
+                        batch,
+                        client.fetch_batch_vote_summary(batch.to_vec()).await,
+                    )
+                }),
+        )
+        .await;
+
+        let mut report = VoteSummaryReport::default();
+        for (bucket, batch, result) in batches {
+            match result {
+                Ok(summaries) => {
+                    report.summaries.best_block =
+                        report.summaries.best_block.max(summaries.best_block);
+                    report.summaries.summaries.extend(summaries.summaries);
+                }
+
+                Err(error) => report.failed.push(FailedBatch {
+                    bucket,
+                    tokens: batch.to_vec(),
+                    error,
+                }),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Fetch the proposals of every bucket of the inventory concurrently.
+    ///
+    /// A failing batch does not fail the others, the proposals fetched are
+    /// returned along with the tokens of the failed batches.
     pub async fn fetch_all_proposals(
         &mut self,
-        mut tokens: api::v1::types::TokenInventory,
-    ) -> Result<super::types::Proposals, Box<dyn std::error::Error + Send + Sync>> {
+        tokens: api::v1::types::TokenInventory,
+    ) -> Result<FetchReport, ClientError> {
         log::info!("Fetching all proposals");
 
-        let mut proposals = super::types::Proposals::default();
+        let page_size = self.page_size().await?;
+        let client = &*self;
 
-        self.fetch_proposal(&mut tokens.pre, &mut proposals.pre)
-            .await?;
-        self.fetch_proposal(&mut tokens.abandoned, &mut proposals.abandoned)
-            .await?;
-        self.fetch_proposal(&mut tokens.active, &mut proposals.active)
-            .await?;
-        self.fetch_proposal(&mut tokens.approved, &mut proposals.approved)
-            .await?;
-        self.fetch_proposal(&mut tokens.censored, &mut proposals.censored)
-            .await?;
-        self.fetch_proposal(&mut tokens.unreviewed, &mut proposals.unreviewed)
-            .await?;
-        self.fetch_proposal(&mut tokens.rejected, &mut proposals.rejected)
-            .await?;
+        let buckets = future::join_all(api::v1::types::TokenInventory::BUCKETS.iter().map(
+            |bucket| {
+                let tokens = tokens.bucket(bucket).cloned().unwrap_or_default();
+                async move {
+                    (
+                        *bucket,
+                        client.fetch_proposal(bucket, &tokens, page_size).await,
+                    )
+                }
+            },
+        ))
+        .await;
+
+        let mut report = FetchReport::default();
+        for (bucket, (proposals, failed)) in buckets {
+            if let Some(e) = report.proposals.bucket_mut(bucket) {
+                *e = proposals;
+            }
+            report.failed.extend(failed);
+        }
+
+        Ok(report)
+    }
 
-        Ok(proposals)
+    /// Returns the policy batch size, fetching the policy if it is unknown.
+    async fn page_size(&mut self) -> Result<usize, ClientError> {
+        if self.policy.proposal_list_page_size == 0 {
+            self.policy = self.fetch_policy().await?;
+        }
+
+        Ok(self.policy.proposal_list_page_size.max(1))
     }
 
+    /// Fetches the proposals of a bucket in batches of `page_size` tokens.
+    /// Proposals are returned in the order of `tokens`, tokens unknown to
+    /// politeiawww are skipped.
     async fn fetch_proposal(
-        &mut self,
-        tokens: &mut Vec<String>,
-        proposals: &mut api::v1::types::ProposalsResult,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // Fetch proposals also not exceeding max proposal list to fetch.
-        while !tokens.is_empty() {
-            let split_at = if (tokens.len() as i64 - self.policy.proposal_list_page_size as i64) < 0
-            {
-                tokens.len() - 1
-            } else {
-                tokens.len() - self.policy.proposal_list_page_size
-            };
+        &self,
+        bucket: &'static str,
+        tokens: &[String],
+        page_size: usize,
+    ) -> (api::v1::types::ProposalsResult, Vec<FailedBatch>) {
+        let batches =
+            future::join_all(tokens.chunks(page_size).map(|batch| async move {
+                (batch, self.fetch_batch_proposal(batch.to_vec()).await)
+            }))
+            .await;
 
-            let a = self
-                .fetch_batch_proposal(tokens.split_off(split_at))
-                .await?;
-            let iter = a.proposals.iter();
+        let mut fetched = HashMap::new();
+        let mut failed = Vec::new();
+        for (batch, result) in batches {
+            match result {
+                Ok(e) => fetched.extend(
+                    e.proposals
+                        .into_iter()
+                        .map(|proposal| (proposal.censorship_record.token.clone(), proposal)),
+                ),
 
-            for prop in iter {
-                proposals.proposals.push(prop.clone())
+                Err(error) => failed.push(FailedBatch {
+                    bucket,
+                    tokens: batch.to_vec(),
+                    error,
+                }),
             }
         }
 
-        Ok(())
+        let proposals = tokens
+            .iter()
+            .filter_map(|token| fetched.remove(token))
+            .collect();
+
+        (api::v1::types::ProposalsResult { proposals }, failed)
+    }
+}
+
+/// Proposals fetched by [Client::fetch_all_proposals] and the batches that
+/// failed.
+#[derive(Default, Debug)]
+pub struct FetchReport {
+    pub proposals: super::types::Proposals,
+    pub failed: Vec<FailedBatch>,
+}
+
+/// Vote summaries fetched by [Client::fetch_vote_summaries] and the batches
+/// that failed.
+#[derive(Default, Debug)]
+pub struct VoteSummaryReport {
+    pub summaries: api::v1::types::BatchVoteSummaryResult,
+    pub failed: Vec<FailedBatch>,
+}
+
+/// Batch of tokens that could not be fetched.
+#[derive(Debug)]
+pub struct FailedBatch {
+    /// Inventory bucket of the tokens.
+    pub bucket: &'static str,
+    pub tokens: Vec<String>,
+    pub error: ClientError,
+}
+
+impl FetchReport {
+    /// Tokens of every failed batch.
+    pub fn failed_tokens(&self) -> impl Iterator<Item = &String> {
+        self.failed.iter().flat_map(|batch| batch.tokens.iter())
+    }
+
+    /// Reports if every batch was fetched.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Reads a response body, turning non-2xx responses into a [ClientError].
+async fn read_response(response: reqwest::Response) -> Result<Vec<u8>, ClientError> {
+    let status = response.status();
+
+    if retry::is_rate_limited(status) {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(retry::parse_retry_after);
+
+        return Err(ClientError::RateLimited {
+            status,
+            retry_after,
+        });
+    }
+
+    let body = response.bytes().await?;
+
+    if status.is_success() {
+        return Ok(body.to_vec());
+    }
+
+    match serde_json::from_slice::<api::v1::errors::UserError>(&body) {
+        // Politeiawww returns user errors as 4xx, 5xx error codes are
+        // server side timestamps and carry no meaning to the client.
+        Ok(e) if status.is_client_error() => Err(ClientError::Api {
+            status,
+            code: api::v1::errors::ErrorCode::from(e.error_code),
+            context: e.error_context,
+        }),
+
+        _ => Err(ClientError::Status(status)),
     }
 }
 
 /// Update proposals at 10minute intervals.
-pub(crate) async fn update_proposals(
-    tokens: Arc<RwLock<api::v1::types::TokenInventory>>,
-    policy: Arc<RwLock<api::v1::types::Policy>>,
-    proposal_mapper: Arc<RwLock<HashMap<String, api::v1::types::Proposal>>>,
+pub async fn update_proposals(
+    mut client: Client,
+    cache: Cache,
+    cache_path: Option<std::path::PathBuf>,
 ) {
-    log::trace!("Starting cache store updater.");
-
-    let mut client = Client::new().unwrap();
+    log::trace!("Starting cache store updater against {}.", client.host());
 
     loop {
         log::trace!("Updating cache store.");
-        let new_tokens = match client.fetch_tokens().await {
-            Ok(e) => e,
-            Err(e) => {
-                log::error!("Error fetching proposal tokens, error: {}", e);
-                log::trace!(
-                    "Retrying update cache in {} seconds",
-                    MAX_TIME_CACHE_UPDATE_SECS
+
+        match refresh_cache(&mut client, &cache).await {
+            Ok(true) => {
+                if let Some(path) = &cache_path {
+                    if let Err(e) = cache.save(path).await {
+                        log::error!("Error saving cache snapshot, error: {}", e);
+                    }
+                }
+            }
+
+            Ok(false) => {}
+
+            Err(e) => log::error!("Error updating cache store, error: {}", e),
+        }
+
+        log::trace!("Updating cache in {} seconds", MAX_TIME_CACHE_UPDATE_SECS);
+        time::delay_for(time::Duration::from_secs(MAX_TIME_CACHE_UPDATE_SECS)).await;
+    }
+}
+
+/// Refresh the cache once from politeiawww, returns true if anything changed.
+pub async fn refresh_cache(client: &mut Client, cache: &Cache) -> Result<bool, ClientError> {
+    let mut changed = false;
+
+    let new_tokens = client.fetch_tokens().await?;
+    client.policy = client.fetch_policy().await?;
+
+    let old_tokens = cache.tokens.read().await.clone();
+    let old_versions: HashMap<String, String> = cache
+        .proposals
+        .read()
+        .await
+        .iter()
+        .map(|(token, proposal)| (token.clone(), proposal.version.clone()))
+        .collect();
+
+    let stale = {
+        let proposals = cache.proposals.read().await;
+        stale_tokens(&old_tokens, &new_tokens, &proposals)
+    };
+    log::trace!(
+        "Refreshing {} of {} proposals",
+        stale.all_tokens().count(),
+        new_tokens.all_tokens().count()
+    );
+
+    let report = client.fetch_all_proposals(stale).await?;
+    for batch in &report.failed {
+        log::error!(
+            "Error fetching {} {} proposals, error: {}",
+            batch.tokens.len(),
+            batch.bucket,
+            batch.error
+        );
+    }
+
+    // Failed tokens keep their previous bucket so the next refresh sees them
+    // as stale and retries them.
+    let failed = report.failed_tokens().count();
+    let new_tokens = defer_failed_tokens(&old_tokens, new_tokens, report.failed_tokens());
+
+    let mut new_proposals = report.proposals;
+    let fetched = new_proposals
+        .buckets()
+        .iter()
+        .map(|bucket| bucket.proposals.len())
+        .sum();
+
+    let mut refused = 0;
+    if !client.policy.backend_public_key.is_empty() {
+        refused = verify_proposals(&mut new_proposals, &client.policy.backend_public_key);
+        if refused > 0 {
+            log::warn!("Refused {} proposals failing verification", refused);
+        }
+    }
+
+    // Politeiawww reordering a bucket is stored but is not a change.
+    if old_tokens != new_tokens {
+        *cache.tokens.write().await = new_tokens.clone();
+        changed |= !same_inventory(&old_tokens, &new_tokens);
+    }
+
+    let is_proposal_same = proposals_is_same(new_proposals.clone(), &cache.proposals).await;
+
+    if !is_proposal_same {
+        let updated = set_proposals(new_proposals, &cache.proposals).await;
+
+        {
+            let proposals = cache.proposals.read().await;
+            let mut search = cache.search.write().await;
+            for token in &updated {
+                if let Some(proposal) = proposals.get(token) {
+                    search.upsert(proposal, &client.policy.index_filename);
+                }
+            }
+        }
+
+        changed = true;
+    }
+
+    let evicted = evict_proposals(&new_tokens, &cache.proposals).await;
+    if !evicted.is_empty() {
+        log::info!(
+            "Evicted {} proposals no longer in the inventory",
+            evicted.len()
+        );
+
+        let mut search = cache.search.write().await;
+        let mut versions = cache.versions.write().await;
+        let mut comments = cache.comments.write().await;
+        let mut version_failures = cache.version_failures.write().await;
+        let mut comment_failures = cache.comment_failures.write().await;
+        for token in &evicted {
+            search.remove(token);
+            versions.remove(token);
+            version_failures.remove(token);
+            comments.remove(token);
+            comment_failures.remove(token);
+        }
+
+        changed = true;
+    }
+
+    if changed {
+        *cache.rfps.write().await = super::rfp::build_index(&*cache.proposals.read().await);
+    }
+
+    // The first refresh populates an empty cache, everything would be new.
+    let mut changes = Vec::new();
+    if old_tokens.all_tokens().next().is_some() {
+        let proposals = cache.proposals.read().await;
+        changes.extend(events::inventory_events(
+            &old_tokens,
+            &new_tokens,
+            &proposals,
+        ));
+        changes.extend(events::version_events(&old_versions, &proposals));
+    }
+
+    if update_versions(client, cache).await {
+        changed = true;
+    }
+
+    if update_comments(client, cache).await {
+        changed = true;
+    }
+
+    match client.fetch_vote_summaries(&new_tokens).await {
+        Ok(report) => {
+            for batch in &report.failed {
+                log::error!(
+                    "Error fetching vote summaries of {} {} proposals, error: {}",
+                    batch.tokens.len(),
+                    batch.bucket,
+                    batch.error
                 );
-                time::delay_for(time::Duration::from_secs(MAX_TIME_CACHE_UPDATE_SECS)).await;
-                continue;
             }
-        };
 
-        let old_tokens = tokens.read().await;
-        if *old_tokens != new_tokens {
-            drop(old_tokens);
+            let old_summaries = cache.vote_summaries.read().await.clone();
+            let mut new_summaries = report.summaries;
+            // Failed batches keep their previous summaries until a later
+            // refresh fetches them.
+            if !report.failed.is_empty() {
+                new_summaries.best_block = new_summaries.best_block.max(old_summaries.best_block);
+                for token in report.failed.iter().flat_map(|batch| &batch.tokens) {
+                    if let Some(summary) = old_summaries.summaries.get(token) {
+                        new_summaries
+                            .summaries
+                            .insert(token.clone(), summary.clone());
+                    }
+                }
+            }
+
+            if new_summaries != old_summaries {
+                if !old_summaries.summaries.is_empty() {
+                    changes.extend(events::vote_events(
+                        &old_summaries.summaries,
+                        &new_summaries.summaries,
+                    ));
+                }
+                *cache.vote_summaries.write().await = new_summaries;
+                changed = true;
+            }
+        }
+
+        Err(e) => log::error!("Error fetching vote summaries, error: {}", e),
+    }
+
+    if client.policy != *cache.policy.read().await {
+        *cache.policy.write().await = client.policy.clone();
+        changed = true;
+    }
+
+    cache
+        .stats
+        .write()
+        .await
+        .record_refresh(fetched, failed, refused, evicted.len());
+
+    cache.events.publish(changes);
+
+    Ok(changed)
+}
+
+/// Fetches the superseded versions of cached proposals missing from the
+/// version history, returns true if the history changed. The latest version
+/// is only kept in the proposal cache.
+///
+/// Prior versions are verified like the latest ones, versions that fail to
+/// fetch or verify are retried with a growing delay rather than every refresh.
+async fn update_versions(client: &Client, cache: &Cache) -> bool {
+    let mut changed = false;
+    let mut missing = Vec::new();
+    let now = std::time::Instant::now();
+
+    {
+        let proposals = cache.proposals.read().await;
+        let mut versions = cache.versions.write().await;
+        let failures = cache.version_failures.read().await;
+
+        for (token, proposal) in proposals.iter() {
+            let latest = match proposal.version.parse::<u32>() {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+
+            // Snapshots taken before the latest version moved out of the
+            // history still hold it.
+            if let Some(history) = versions.get_mut(token) {
+                let superseded = history.len();
+                history.retain(|version, _| *version < latest);
+                changed |= history.len() != superseded;
+            }
+
+            let history = versions.get(token);
+            let failed = failures.get(token);
+            for version in 1..latest {
+                let deferred = failed
+                    .and_then(|failed| failed.get(&version))
+                    .is_some_and(|failure| failure.retry_at > now);
+                let known = history.is_some_and(|history| history.contains_key(&version));
+
+                if !deferred && !known {
+                    missing.push((token.clone(), version));
+                }
+            }
+        }
+    }
+
+    if missing.len() > MAX_VERSION_FETCHES_PER_REFRESH {
+        log::info!(
+            "Deferring {} prior proposal versions to the next refresh",
+            missing.len() - MAX_VERSION_FETCHES_PER_REFRESH
+        );
+        missing.truncate(MAX_VERSION_FETCHES_PER_REFRESH);
+    }
+
+    // Requests in flight are bounded by the client.
+    let results = future::join_all(missing.into_iter().map(|(token, version)| async move {
+        let result = fetch_version(client, &token, version).await;
+        (token, version, result)
+    }))
+    .await;
+
+    let mut versions = cache.versions.write().await;
+    let mut failures = cache.version_failures.write().await;
+    for (token, version, result) in results {
+        match result {
+            Ok(proposal) => {
+                versions
+                    .entry(token.clone())
+                    .or_default()
+                    .insert(version, proposal);
+                if let Some(failed) = failures.get_mut(&token) {
+                    failed.remove(&version);
+                    if failed.is_empty() {
+                        failures.remove(&token);
+                    }
+                }
+                changed = true;
+            }
 
-            let mut tokens = tokens.write().await;
-            *tokens = new_tokens.clone();
+            Err(e) => {
+                let failure = failures
+                    .entry(token.clone())
+                    .or_default()
+                    .entry(version)
+                    .or_insert(FetchFailure {
+                        failures: 0,
+                        retry_at: now,
+                    });
+                failure.failures += 1;
+
+                let delay = failure_delay(
+                    failure.failures,
+                    VERSION_RETRY_DELAY_SECS,
+                    MAX_VERSION_RETRY_DELAY_SECS,
+                );
+                failure.retry_at = now + delay;
+
+                log::error!(
+                    "Error fetching proposal {} version {}, retrying in {} seconds, error: {}",
+                    token,
+                    version,
+                    delay.as_secs(),
+                    e
+                );
+            }
         }
+    }
+
+    changed
+}
+
+/// Fetches a prior proposal version, verifying it against the backend key
+/// when the policy is known.
+async fn fetch_version(
+    client: &Client,
+    token: &str,
+    version: u32,
+) -> Result<api::v1::types::Proposal, String> {
+    let proposal = client
+        .fetch_proposal_details(token, Some(version))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !client.policy.backend_public_key.is_empty() {
+        proposal
+            .verify(&client.policy.backend_public_key)
+            .map_err(|e| format!("verification failed: {}", e))?;
+    }
+
+    Ok(proposal)
+}
+
+/// Delay before refetching data that failed `failures` times, starting at
+/// `initial_secs` and doubling up to `max_secs`.
+fn failure_delay(failures: u32, initial_secs: u64, max_secs: u64) -> time::Duration {
+    let secs = initial_secs
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(max_secs);
+
+    time::Duration::from_secs(secs)
+}
+
+/// Fetches the comments of cached proposals whose comment count changed since
+/// their comments were last fetched, returns true if any comments changed.
+///
+/// Comments that fail to fetch are retried with a growing delay rather than
+/// every refresh.
+async fn update_comments(client: &Client, cache: &Cache) -> bool {
+    let now = std::time::Instant::now();
+    let stale: Vec<(String, usize)> = {
+        let proposals = cache.proposals.read().await;
+        let comments = cache.comments.read().await;
+        let failures = cache.comment_failures.read().await;
+
+        proposals
+            .iter()
+            .filter(|(token, proposal)| match comments.get(*token) {
+                Some(thread) => thread.number_of_comments != proposal.number_of_comments,
+                None => proposal.number_of_comments > 0,
+            })
+            .filter(|(token, _)| {
+                failures
+                    .get(*token)
+                    .is_none_or(|failure| failure.retry_at <= now)
+            })
+            .map(|(token, proposal)| (token.clone(), proposal.number_of_comments))
+            .collect()
+    };
+
+    // Requests in flight are bounded by the client.
+    let results = future::join_all(stale.into_iter().map(
+        |(token, number_of_comments)| async move {
+            let result = client.fetch_comments(&token).await;
+            (token, number_of_comments, result)
+        },
+    ))
+    .await;
+
+    let mut changed = false;
+    let mut comments = cache.comments.write().await;
+    let mut failures = cache.comment_failures.write().await;
+    for (token, number_of_comments, result) in results {
+        match result {
+            Ok(result) => {
+                failures.remove(&token);
+                comments.insert(
+                    token,
+                    CachedComments {
+                        number_of_comments,
+                        comments: result.comments,
+                    },
+                );
+                changed = true;
+            }
 
-        let new_proposals = match client.fetch_all_proposals(new_tokens).await {
-            Ok(e) => e,
             Err(e) => {
-                log::error!("Error fetching proposals, error: {}", e);
-                log::trace!(
-                    "Retrying update cache in {} seconds",
-                    MAX_TIME_CACHE_UPDATE_SECS
+                let failure = failures.entry(token.clone()).or_insert(FetchFailure {
+                    failures: 0,
+                    retry_at: now,
+                });
+                failure.failures += 1;
+
+                let delay = failure_delay(
+                    failure.failures,
+                    COMMENT_RETRY_DELAY_SECS,
+                    MAX_COMMENT_RETRY_DELAY_SECS,
+                );
+                failure.retry_at = now + delay;
+
+                log::error!(
+                    "Error fetching comments of proposal {}, retrying in {} seconds, error: {}",
+                    token,
+                    delay.as_secs(),
+                    e
                 );
-                time::delay_for(time::Duration::from_secs(MAX_TIME_CACHE_UPDATE_SECS)).await;
-                continue;
             }
-        };
+        }
+    }
+
+    changed
+}
+
+/// Inventory buckets of vetted proposals, the ones vote summaries exist for.
+const VETTED_BUCKETS: [&str; 4] = ["pre", "active", "approved", "rejected"];
+
+/// Inventory buckets whose proposals can still be edited, changing their
+/// version and timestamp, or commented on, changing their comment count.
+const MUTABLE_BUCKETS: [&str; 3] = ["pre", "active", "unreviewed"];
 
-        let is_proposal_same = proposals_is_same(new_proposals.clone(), &proposal_mapper).await;
+/// Returns the tokens of `new_tokens` that have to be fetched: proposals that
+/// are not cached yet, moved inventory bucket since the last refresh or sit in
+/// a bucket where they can still be edited.
+fn stale_tokens(
+    old_tokens: &api::v1::types::TokenInventory,
+    new_tokens: &api::v1::types::TokenInventory,
+    proposals: &HashMap<String, api::v1::types::Proposal>,
+) -> api::v1::types::TokenInventory {
+    let mut stale = api::v1::types::TokenInventory::default();
 
-        if !is_proposal_same {
-            set_proposals(new_proposals, &proposal_mapper).await;
+    for (bucket, tokens) in new_tokens.buckets() {
+        let old_bucket: HashSet<&String> =
+            old_tokens.bucket(bucket).into_iter().flatten().collect();
+        let stale_bucket = stale.bucket_mut(bucket).unwrap();
+
+        for token in tokens {
+            if !proposals.contains_key(token)
+                || !old_bucket.contains(token)
+                || MUTABLE_BUCKETS.contains(&bucket)
+            {
+                stale_bucket.push(token.clone());
+            }
         }
+    }
 
-        if client.policy != *policy.read().await {
-            *policy.write().await = client.policy.clone();
+    stale
+}
+
+/// Moves tokens that failed to fetch back to their position in the old
+/// inventory, dropping tokens the old inventory did not have. Tokens that did
+/// not change bucket are left in place.
+fn defer_failed_tokens<'a>(
+    old_tokens: &api::v1::types::TokenInventory,
+    mut new_tokens: api::v1::types::TokenInventory,
+    failed: impl Iterator<Item = &'a String>,
+) -> api::v1::types::TokenInventory {
+    for token in failed {
+        let old_bucket = old_tokens.bucket_of(token);
+        if old_bucket.is_some() && old_bucket == new_tokens.bucket_of(token) {
+            continue;
         }
 
-        log::trace!("Updating cache in {} seconds", MAX_TIME_CACHE_UPDATE_SECS);
-        time::delay_for(time::Duration::from_secs(MAX_TIME_CACHE_UPDATE_SECS)).await;
+        for bucket in api::v1::types::TokenInventory::BUCKETS.iter() {
+            if let Some(tokens) = new_tokens.bucket_mut(bucket) {
+                tokens.retain(|t| t != token);
+            }
+        }
+
+        let bucket = match old_bucket {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let position = old_tokens
+            .bucket(bucket)
+            .and_then(|tokens| tokens.iter().position(|t| t == token))
+            .unwrap_or_default();
+        if let Some(tokens) = new_tokens.bucket_mut(bucket) {
+            tokens.insert(position.min(tokens.len()), token.clone());
+        }
+    }
+
+    new_tokens
+}
+
+/// Reports if two inventories hold the same tokens in the same buckets,
+/// whatever their order.
+fn same_inventory(a: &api::v1::types::TokenInventory, b: &api::v1::types::TokenInventory) -> bool {
+    api::v1::types::TokenInventory::BUCKETS
+        .iter()
+        .all(|bucket| {
+            let a: HashSet<&String> = a.bucket(bucket).into_iter().flatten().collect();
+            let b: HashSet<&String> = b.bucket(bucket).into_iter().flatten().collect();
+            a == b
+        })
+}
+
+/// Drops proposals whose censorship record fails verification against the
+/// backend public key, returns the number of proposals dropped.
+fn verify_proposals(proposals: &mut super::types::Proposals, backend_public_key: &str) -> usize {
+    let mut refused = 0;
+
+    for bucket in proposals.buckets_mut() {
+        bucket
+            .proposals
+            .retain(|proposal| match proposal.verify(backend_public_key) {
+                Ok(()) => true,
+
+                Err(e) => {
+                    log::warn!(
+                        "Refusing proposal {}, verification failed: {}",
+                        proposal.censorship_record.token,
+                        e
+                    );
+                    refused += 1;
+                    false
+                }
+            });
     }
+
+    refused
+}
+
+/// Removes cached proposals that are no longer part of the token inventory,
+/// returns the tokens evicted.
+async fn evict_proposals(
+    inventory: &api::v1::types::TokenInventory,
+    proposal_mapper: &Arc<RwLock<HashMap<String, api::v1::types::Proposal>>>,
+) -> Vec<String> {
+    let live: HashSet<&String> = inventory.all_tokens().collect();
+
+    let mut proposal_mapper = proposal_mapper.write().await;
+    let evicted: Vec<String> = proposal_mapper
+        .keys()
+        .filter(|token| !live.contains(token))
+        .cloned()
+        .collect();
+
+    for token in &evicted {
+        proposal_mapper.remove(token);
+    }
+
+    evicted
 }
 
 async fn proposals_is_same(
@@ -312,17 +1175,23 @@ async fn proposals_is_same(
     true
 }
 
+/// Stores fetched proposals, returns the tokens of proposals that were added
+/// or changed.
 async fn set_proposals(
     new_proposal: super::types::Proposals,
     proposal_mapper: &Arc<RwLock<HashMap<String, api::v1::types::Proposal>>>,
-) {
+) -> Vec<String> {
     let mut proposal_mapper = proposal_mapper.write().await;
+    let mut updated = Vec::new();
 
     let mut setter = |proposal: api::v1::types::ProposalsResult| {
         for proposal in proposal.proposals {
             let token = proposal.censorship_record.token.clone();
 
-            proposal_mapper.insert(token, proposal);
+            if proposal_mapper.get(&token) != Some(&proposal) {
+                updated.push(token.clone());
+                proposal_mapper.insert(token, proposal);
+            }
         }
     };
 
@@ -333,4 +1202,6 @@ async fn set_proposals(
     setter(new_proposal.pre);
     setter(new_proposal.rejected);
     setter(new_proposal.unreviewed);
+
+    updated
 }
@@ -0,0 +1,55 @@
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// Url schemes links and images may point to.
+const ALLOWED_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Renders proposal markdown to HTML.
+///
+/// Proposal bodies are user submitted and served from our origin, raw HTML
+/// embedded in the markdown is escaped rather than passed through and link
+/// and image destinations with a scheme other than http, https or mailto are
+/// replaced.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) => Event::Text(raw),
+        Event::Start(tag) => Event::Start(sanitize_tag(tag)),
+        Event::End(tag) => Event::End(sanitize_tag(tag)),
+        event => event,
+    });
+
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, parser);
+    output
+}
+
+fn sanitize_tag(tag: Tag) -> Tag {
+    match tag {
+        Tag::Link(kind, url, title) => Tag::Link(kind, sanitize_url(url), title),
+        Tag::Image(kind, url, title) => Tag::Image(kind, sanitize_url(url), title),
+        tag => tag,
+    }
+}
+
+/// Replaces urls with a disallowed scheme by `#`, relative urls are kept.
+fn sanitize_url(url: CowStr) -> CowStr {
+    // Browsers ignore whitespace and control characters within a scheme.
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+
+    let scheme = match normalized.find([':', '/', '?', '#']) {
+        Some(i) if normalized[i..].starts_with(':') => normalized[..i].to_ascii_lowercase(),
+        _ => return url,
+    };
+
+    if ALLOWED_SCHEMES.contains(&scheme.as_str()) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
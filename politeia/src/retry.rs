@@ -0,0 +1,107 @@
+use super::error::ClientError;
+use rand::Rng;
+use reqwest::StatusCode;
+use std::time::{Duration, SystemTime};
+
+/// How [Client](super::model::Client) retries failed requests.
+///
+/// Retries back off exponentially from `initial_backoff`, doubling up to
+/// `max_backoff`, each delay is picked at random between half and the full
+/// backoff so concurrent requests do not retry in lockstep. A `Retry-After`
+/// sent with a 429 or 503 response replaces the backoff, capped at
+/// `max_backoff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt, zero disables retrying.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy failing requests on the first error.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Delay before retrying a request that failed `attempt` times with
+    /// `error`, `None` if it should not be retried.
+    ///
+    /// Requests that are not idempotent are only retried when the server
+    /// could not have processed them: connection failures and rate limiting.
+    pub fn retry_delay(
+        &self,
+        attempt: u32,
+        error: &ClientError,
+        idempotent: bool,
+    ) -> Option<Duration> {
+        if attempt > self.max_retries {
+            return None;
+        }
+
+        let retryable = match error {
+            ClientError::RateLimited { .. } => true,
+            ClientError::Network(e) if !idempotent => e.is_connect(),
+            e => idempotent && e.is_transient(),
+        };
+        if !retryable {
+            return None;
+        }
+
+        match error {
+            ClientError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } => Some((*retry_after).min(self.max_backoff)),
+
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Jittered exponential backoff after `attempt` failed attempts.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        let millis = backoff.as_millis() as u64;
+        if millis < 2 {
+            return backoff;
+        }
+
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2, millis + 1))
+    }
+}
+
+/// Reports if a response status asks the client to slow down.
+pub fn is_rate_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Parses a `Retry-After` header given in seconds or as an HTTP date, dates
+/// in the past ask for no delay.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
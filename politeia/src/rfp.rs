@@ -0,0 +1,44 @@
+use super::cache::VersionHistory;
+use politeia_api::v1::types::Proposal;
+use std::collections::HashMap;
+
+/// Tokens of the submissions of each RFP keyed by the RFP token, submissions
+/// are ordered by publication time.
+pub type RfpIndex = HashMap<String, Vec<String>>;
+
+/// Builds the RFP index from the `link_to` of every cached proposal.
+pub fn build_index(proposals: &HashMap<String, Proposal>) -> RfpIndex {
+    let mut index = RfpIndex::new();
+
+    for (token, proposal) in proposals {
+        if proposal.link_to.is_empty() {
+            continue;
+        }
+
+        index
+            .entry(proposal.link_to.clone())
+            .or_default()
+            .push(token.clone());
+    }
+
+    for submissions in index.values_mut() {
+        submissions.sort_by_key(|token| (proposals[token].pubished_at, token.clone()));
+    }
+
+    index
+}
+
+/// Returns when a proposal was submitted, the timestamp of its first known
+/// version. Publication happens once an admin reviews the submission and
+/// later edits update the proposal timestamp, neither is the submission time.
+pub fn submitted_at(proposal: &Proposal, versions: &VersionHistory) -> i64 {
+    versions
+        .get(&proposal.censorship_record.token)
+        .and_then(|history| history.values().next())
+        .map_or(proposal.timestamp, |first| first.timestamp)
+}
+
+/// Reports if a submission was submitted after the RFP `link_by` deadline.
+pub fn past_deadline(rfp: &Proposal, submitted_at: i64) -> bool {
+    rfp.link_by != 0 && submitted_at > rfp.link_by
+}
@@ -0,0 +1,129 @@
+use politeia_api::v1::types::Proposal;
+use std::collections::{HashMap, HashSet};
+
+/// Ranking weight of terms found in the proposal name.
+const NAME_WEIGHT: f64 = 3.0;
+/// Ranking weight of terms found in the author username.
+const USERNAME_WEIGHT: f64 = 2.0;
+/// Ranking weight of terms found in the proposal index file.
+const BODY_WEIGHT: f64 = 1.0;
+
+/// Inverted index over cached proposals names, usernames and index files.
+#[derive(Default, Debug)]
+pub struct SearchIndex {
+    /// Term to weighted term frequency per proposal token.
+    postings: HashMap<String, HashMap<String, f64>>,
+    /// Terms indexed for each proposal token, used to drop stale postings.
+    documents: HashMap<String, HashSet<String>>,
+}
+
+impl SearchIndex {
+    /// Builds an index over every proposal.
+    pub fn build<'a, I>(proposals: I, index_filename: &str) -> SearchIndex
+    where
+        I: IntoIterator<Item = &'a Proposal>,
+    {
+        let mut index = SearchIndex::default();
+        for proposal in proposals {
+            index.upsert(proposal, index_filename);
+        }
+        index
+    }
+
+    /// Indexes a proposal, replacing any previous version of it.
+    pub fn upsert(&mut self, proposal: &Proposal, index_filename: &str) {
+        let token = &proposal.censorship_record.token;
+        self.remove(token);
+
+        let mut frequencies: HashMap<String, f64> = HashMap::new();
+        let mut add = |text: &str, weight: f64| {
+            for term in terms(text) {
+                *frequencies.entry(term).or_default() += weight;
+            }
+        };
+
+        add(&proposal.name, NAME_WEIGHT);
+        add(&proposal.username, USERNAME_WEIGHT);
+
+        match proposal.index_markdown(index_filename) {
+            Ok(body) => add(&body, BODY_WEIGHT),
+            Err(e) => log::trace!("Indexing proposal {} without body: {}", token, e),
+        }
+
+        for (term, frequency) in &frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(token.clone(), *frequency);
+        }
+
+        self.documents
+            .insert(token.clone(), frequencies.into_keys().collect());
+    }
+
+    /// Removes a proposal from the index.
+    pub fn remove(&mut self, token: &str) {
+        let terms = match self.documents.remove(token) {
+            Some(e) => e,
+            None => return,
+        };
+
+        for term in terms {
+            if let Some(posting) = self.postings.get_mut(&term) {
+                posting.remove(token);
+                if posting.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Returns the tokens of proposals matching every term of the query,
+    /// ordered by descending score.
+    ///
+    /// Scores are the sum over query terms of the weighted term frequency
+    /// scaled by the term's inverse document frequency.
+    pub fn search(&self, query: &str) -> Vec<(String, f64)> {
+        let query_terms: HashSet<String> = terms(query).collect();
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let documents = self.documents.len() as f64;
+        let mut scores: HashMap<&String, (usize, f64)> = HashMap::new();
+
+        for term in &query_terms {
+            let posting = match self.postings.get(term) {
+                Some(e) => e,
+                None => return Vec::new(),
+            };
+
+            let idf = (1.0 + documents / posting.len() as f64).ln();
+            for (token, frequency) in posting {
+                let score = scores.entry(token).or_default();
+                score.0 += 1;
+                score.1 += frequency * idf;
+            }
+        }
+
+        let mut results: Vec<(String, f64)> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == query_terms.len())
+            .map(|(token, (_, score))| (token.clone(), score))
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        results
+    }
+}
+
+/// Splits text into lowercase alphanumeric terms.
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
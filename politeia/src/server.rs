@@ -1,13 +1,29 @@
-use super::types;
+use super::{
+    cache::{self, resolve_token, Cache, CacheStats, CachedComments, ResolveError, VersionHistory},
+    config::Config,
+    diff::ProposalDiff,
+    events::{ChangeEvent, EventBus},
+    model::Client,
+    render,
+    rfp::{self, RfpIndex},
+    search::SearchIndex,
+    types,
+    webhook::{DeliveryLog, Webhooks},
+};
 use actix_cors::Cors;
 use actix_files::{Files as fs, NamedFile};
 use actix_web::{
-    get, http::header, http::StatusCode, post, web, App, HttpServer, Responder, Result,
+    get, http::header, http::StatusCode, post, web, App, HttpResponse, HttpServer, Responder,
+    Result,
 };
 use askama_actix::{Template, TemplateIntoResponse};
-use politeia_api::{v1::errors::ErrorCode, v1::types as v1types};
+use politeia_api::{v1::errors::ErrorCode, v1::files::FileError, v1::types as v1types};
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::RwLock;
+use tokio::{
+    stream::StreamExt,
+    sync::{broadcast::RecvError, RwLock},
+    time,
+};
 
 #[derive(Template)]
 #[template(path = "./dist/home.html")]
@@ -17,22 +33,55 @@ async fn favicon() -> Result<NamedFile> {
     Ok(NamedFile::open("politeia/templates/dist/favicon.ico")?)
 }
 
-pub async fn start_server() -> std::io::Result<()> {
-    let port = std::env::var("PORT").expect("Port env not set.");
-    if port == "" {
-        panic!("Port env is an empty string");
-    }
+pub async fn start_server(config: Config) -> std::io::Result<()> {
+    let client = Client::builder()
+        .host(config.politeia_host)
+        .build()
+        .map_err(std::io::Error::other)?;
 
-    let proposals: HashMap<String, v1types::Proposal> = HashMap::new();
-    let proposal_mapper = Arc::new(RwLock::new(proposals));
+    let cache = match &config.cache_path {
+        Some(path) => match Cache::load(path).await {
+            Ok(cache) => {
+                log::info!(
+                    "Loaded {} proposals from cache snapshot {}",
+                    cache.proposals.read().await.len(),
+                    path.display()
+                );
+                cache
+            }
 
-    let tokens = Arc::new(RwLock::new(v1types::TokenInventory::default()));
-    let policy = Arc::new(RwLock::new(v1types::Policy::default()));
+            Err(e) => {
+                log::error!(
+                    "Error loading cache snapshot {}, error: {}",
+                    path.display(),
+                    e
+                );
+                Cache::default()
+            }
+        },
+
+        None => Cache::default(),
+    };
+
+    let deliveries = Arc::new(RwLock::new(DeliveryLog::default()));
+    // Config rejects webhook targets without a secret.
+    match &config.webhook_secret {
+        Some(secret) if !config.webhooks.is_empty() => {
+            let webhooks =
+                Webhooks::new(config.webhooks.clone(), secret.clone(), deliveries.clone())
+                    .map_err(std::io::Error::other)?;
+
+            log::info!("Delivering webhooks to {} targets", config.webhooks.len());
+            tokio::spawn(webhooks.run(cache.events.subscribe()));
+        }
+
+        _ => {}
+    }
 
     tokio::spawn(super::model::update_proposals(
-        tokens.clone(),
-        policy.clone(),
-        proposal_mapper.clone(),
+        client,
+        cache.clone(),
+        config.cache_path.clone(),
     ));
 
     HttpServer::new(move || {
@@ -43,22 +92,89 @@ pub async fn start_server() -> std::io::Result<()> {
 
         App::new()
             .wrap(cors)
-            .data(tokens.clone())
-            .data(policy.clone())
-            .data(proposal_mapper.clone())
+            .configure(|cfg| configure(cfg, &cache, &deliveries))
             .service(index)
-            .service(fetch_tokens)
-            .service(fetch_proposals)
             .route("/favicon.ico", web::get().to(favicon))
             .service(fs::new("/css", "politeia/templates/dist/css"))
             .service(fs::new("/js", "politeia/templates/dist/js"))
             .service(fs::new("/img", "politeia/templates/dist/img"))
     })
-    .bind(format!("0.0.0.0:{}", port))?
+    .bind(format!("0.0.0.0:{}", config.port))?
     .run()
     .await
 }
 
+/// Registers the API routes and the cache data they are served from.
+pub fn configure(
+    cfg: &mut web::ServiceConfig,
+    cache: &Cache,
+    deliveries: &Arc<RwLock<DeliveryLog>>,
+) {
+    cfg.data(cache.tokens.clone())
+        .data(cache.policy.clone())
+        .data(cache.proposals.clone())
+        .data(cache.vote_summaries.clone())
+        .data(cache.stats.clone())
+        .data(cache.search.clone())
+        .data(cache.versions.clone())
+        .data(cache.comments.clone())
+        .data(cache.rfps.clone())
+        .data(cache.events.clone())
+        .data(deliveries.clone())
+        .service(fetch_tokens)
+        .service(fetch_proposals)
+        .service(vote_status)
+        .service(proposal_body)
+        .service(fetch_proposal)
+        .service(proposal_version)
+        .service(proposal_diff)
+        .service(proposal_comments)
+        .service(rfp_submissions)
+        .service(cache_stats)
+        .service(change_events)
+        .service(webhook_deliveries)
+        .service(list_proposals)
+        .service(search);
+}
+
+/// Proposals per listing page when the policy page size is unknown.
+const DEFAULT_PROPOSALS_PER_PAGE: usize = 20;
+/// Upper bound of proposals returned by a single listing page.
+const MAX_PROPOSALS_PER_PAGE: usize = 100;
+
+/// Search results returned when the query does not set a limit.
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Interval between comments keeping idle event streams open.
+const EVENT_KEEP_ALIVE_SECS: u64 = 15;
+
+/// Error body carrying a politeia error code and its message.
+fn error_body(code: ErrorCode) -> String {
+    serde_json::json!({
+        "code": code,
+        "message": code.to_string(),
+    })
+    .to_string()
+}
+
+/// Error body for a token that could not be resolved, ambiguous prefixes
+/// list the matching tokens as context.
+fn resolve_error_body(token: &str, e: ResolveError) -> String {
+    let code = ErrorCode::StatusInvalidCensorshipToken;
+
+    let mut context = vec![format!("{}: {}", token, e)];
+    if let ResolveError::Ambiguous(tokens) = e {
+        context.extend(tokens);
+    }
+
+    serde_json::json!({
+        "code": code,
+        "message": code.to_string(),
+        "context": context,
+    })
+    .to_string()
+}
+
 #[get("/")]
 async fn index() -> impl Responder {
     let a = HomeTemplate {};
@@ -94,30 +210,22 @@ async fn fetch_proposals(
 
     // Ensure number of proposals requested does not pass limit.
     if tokens.tokens.len() > policy.read().await.proposal_list_page_size {
-        let error_code: u8 = ErrorCode::StatusMaxProposalsExceededPolicy.into();
-        return serde_json::json!({
-            "code" : error_code,
-        })
-        .to_string()
-        .with_status(StatusCode::BAD_REQUEST);
+        return error_body(ErrorCode::StatusMaxProposalsExceededPolicy)
+            .with_status(StatusCode::BAD_REQUEST);
     }
 
+    let prefix_length = policy.read().await.token_prefix_length;
     let mut proposal_result = v1types::ProposalsResult::default();
     let proposals = proposals.read().await;
 
     for token in &tokens.tokens {
-        match proposals.get(token) {
-            Some(e) => {
-                proposal_result.proposals.push(e.clone());
+        match resolve_token(&proposals, token, prefix_length) {
+            Ok(token) => {
+                proposal_result.proposals.push(proposals[&token].clone());
             }
 
-            None => {
-                let error_code: u8 = ErrorCode::StatusInvalidCensorshipToken.into();
-                return serde_json::json!({
-                    "code" : error_code,
-                })
-                .to_string()
-                .with_status(StatusCode::BAD_REQUEST);
+            Err(e) => {
+                return resolve_error_body(token, e).with_status(StatusCode::BAD_REQUEST);
             }
         }
     }
@@ -136,3 +244,501 @@ async fn fetch_proposals(
         }
     }
 }
+
+#[get("/api/v1/votestatus")]
+async fn vote_status(
+    vote_summaries: web::Data<Arc<RwLock<v1types::BatchVoteSummaryResult>>>,
+) -> impl Responder {
+    let vote_summaries = vote_summaries.read().await;
+
+    match serde_json::to_string(&*vote_summaries) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling vote summaries struct, error: {}", e);
+
+            "error sending vote status"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposal/{token}")]
+async fn fetch_proposal(
+    token: web::Path<String>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+) -> impl Responder {
+    let prefix_length = policy.read().await.token_prefix_length;
+    let proposals = proposals.read().await;
+
+    let proposal = match resolve_token(&proposals, &token, prefix_length) {
+        Ok(e) => &proposals[&e],
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match serde_json::to_string(proposal) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling proposal struct, error: {}", e);
+
+            "error sending proposal"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposal/{token}/version/{version}")]
+async fn proposal_version(
+    path: web::Path<(String, u32)>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+    versions: web::Data<Arc<RwLock<VersionHistory>>>,
+) -> impl Responder {
+    let (token, version) = path.into_inner();
+    let prefix_length = policy.read().await.token_prefix_length;
+
+    let proposals = proposals.read().await;
+    let token = match resolve_token(&proposals, &token, prefix_length) {
+        Ok(e) => e,
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let versions = versions.read().await;
+    let proposal = match cache::proposal_version(&proposals, &versions, &token, version) {
+        Some(e) => e,
+
+        None => {
+            return error_body(ErrorCode::StatusInvalidProposalVersion)
+                .with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match serde_json::to_string(proposal) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling proposal struct, error: {}", e);
+
+            "error sending proposal"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposal/{token}/diff")]
+async fn proposal_diff(
+    token: web::Path<String>,
+    query: web::Query<types::DiffQuery>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+    versions: web::Data<Arc<RwLock<VersionHistory>>>,
+) -> impl Responder {
+    let policy = policy.read().await.clone();
+
+    let proposals = proposals.read().await;
+    let token = match resolve_token(&proposals, &token, policy.token_prefix_length) {
+        Ok(e) => e,
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let versions = versions.read().await;
+    let (old, new) = match (
+        cache::proposal_version(&proposals, &versions, &token, query.from),
+        cache::proposal_version(&proposals, &versions, &token, query.to),
+    ) {
+        (Some(old), Some(new)) => (old, new),
+
+        _ => {
+            return error_body(ErrorCode::StatusInvalidProposalVersion)
+                .with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let diff = ProposalDiff::new(old, new, query.from, query.to, &policy.index_filename);
+    drop(versions);
+    drop(proposals);
+
+    match serde_json::to_string(&diff) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling proposal diff struct, error: {}", e);
+
+            "error sending proposal diff"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposal/{token}/comments")]
+async fn proposal_comments(
+    token: web::Path<String>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+    comments: web::Data<Arc<RwLock<HashMap<String, CachedComments>>>>,
+) -> impl Responder {
+    let prefix_length = policy.read().await.token_prefix_length;
+
+    let token = match resolve_token(&*proposals.read().await, &token, prefix_length) {
+        Ok(e) => e,
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let thread = match comments.read().await.get(&token) {
+        Some(e) => types::CommentThread::new(token, &e.comments),
+        None => types::CommentThread::new(token, &[]),
+    };
+
+    match serde_json::to_string(&thread) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling comment thread struct, error: {}", e);
+
+            "error sending comments"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposal/{token}/body")]
+async fn proposal_body(
+    token: web::Path<String>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+) -> impl Responder {
+    let policy = policy.read().await.clone();
+    let proposals = proposals.read().await;
+
+    let proposal = match resolve_token(&proposals, &token, policy.token_prefix_length) {
+        Ok(e) => &proposals[&e],
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match proposal.index_markdown(&policy.index_filename) {
+        Ok(markdown) => render::markdown_to_html(&markdown)
+            .with_status(StatusCode::OK)
+            .with_header(header::CONTENT_TYPE, "text/html; charset=utf-8"),
+
+        Err(FileError::NotFound(_)) => {
+            error_body(ErrorCode::StatusProposalMissingFiles).with_status(StatusCode::NOT_FOUND)
+        }
+
+        Err(e) => {
+            log::error!(
+                "Error decoding proposal {} index file, error: {}",
+                *token,
+                e
+            );
+
+            "error rendering proposal"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/stats")]
+async fn cache_stats(stats: web::Data<Arc<RwLock<CacheStats>>>) -> impl Responder {
+    let stats = stats.read().await;
+
+    match serde_json::to_string(&*stats) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling cache stats struct, error: {}", e);
+
+            "error sending stats"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Streams proposal change events as server-sent events.
+#[get("/api/v1/events")]
+async fn change_events(events: web::Data<EventBus>) -> HttpResponse {
+    let keep_alive = time::interval(time::Duration::from_secs(EVENT_KEEP_ALIVE_SECS))
+        .map(|_| ": keep-alive\n\n".to_string());
+
+    let changes = events.subscribe().filter_map(|event| match event {
+        Ok(event) => event_message(&event),
+
+        // Tell the subscriber to resynchronise from the REST API.
+        Err(RecvError::Lagged(skipped)) => Some(format!(
+            "event: lagged\ndata: {{\"skipped\":{}}}\n\n",
+            skipped
+        )),
+
+        Err(RecvError::Closed) => None,
+    });
+
+    let stream = changes
+        .merge(keep_alive)
+        .map(|message| Ok::<_, actix_web::Error>(web::Bytes::from(message)));
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .streaming(stream)
+}
+
+/// Formats a change event as a server-sent event message, events failing
+/// to marshal are skipped.
+fn event_message(event: &ChangeEvent) -> Option<String> {
+    match serde_json::to_string(event) {
+        Ok(e) => Some(format!("event: {}\ndata: {}\n\n", event.name(), e)),
+
+        Err(e) => {
+            log::error!("Error marshalling change event struct, error: {}", e);
+            None
+        }
+    }
+}
+
+/// Lists the most recent webhook deliveries, targets are reduced to their
+/// host as webhook urls may embed secrets.
+#[get("/api/v1/webhooks/deliveries")]
+async fn webhook_deliveries(deliveries: web::Data<Arc<RwLock<DeliveryLog>>>) -> impl Responder {
+    let deliveries = deliveries.read().await;
+
+    match serde_json::to_string(&*deliveries) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling delivery log struct, error: {}", e);
+
+            "error sending webhook deliveries"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/proposals")]
+async fn list_proposals(
+    query: web::Query<types::ListQuery>,
+    tokens: web::Data<Arc<RwLock<v1types::TokenInventory>>>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1);
+    let per_page = match query.per_page {
+        Some(per_page) => per_page,
+
+        // Policy is unknown until the first cache refresh completes, a
+        // policy page size above our bound must not fail default requests.
+        None => match policy.read().await.proposal_list_page_size {
+            0 => DEFAULT_PROPOSALS_PER_PAGE,
+            page_size => page_size.min(MAX_PROPOSALS_PER_PAGE),
+        },
+    };
+    if page == 0 || per_page == 0 || per_page > MAX_PROPOSALS_PER_PAGE {
+        return error_body(ErrorCode::StatusInvalidInput).with_status(StatusCode::BAD_REQUEST);
+    }
+
+    let tokens = tokens.read().await;
+    let bucket = match tokens.bucket(&query.status) {
+        Some(e) => e,
+
+        None => {
+            return error_body(ErrorCode::StatusInvalidInput).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let proposals = proposals.read().await;
+    let page_result = types::ProposalPage {
+        status: query.status.clone(),
+        page,
+        per_page,
+        total: bucket.len(),
+        proposals: bucket
+            .iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .filter_map(|token| proposals.get(token))
+            .map(types::ProposalSummary::from)
+            .collect(),
+    };
+
+    drop(proposals);
+    drop(tokens);
+
+    match serde_json::to_string(&page_result) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling proposal page struct, error: {}", e);
+
+            "error sending proposals"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[get("/api/v1/search")]
+async fn search(
+    query: web::Query<types::SearchQuery>,
+    tokens: web::Data<Arc<RwLock<v1types::TokenInventory>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+    search_index: web::Data<Arc<RwLock<SearchIndex>>>,
+) -> impl Responder {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_PROPOSALS_PER_PAGE);
+
+    let tokens = tokens.read().await;
+    if let Some(status) = &query.status {
+        if tokens.bucket(status).is_none() {
+            return error_body(ErrorCode::StatusInvalidInput).with_status(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let matches = search_index.read().await.search(&query.q);
+    let proposals = proposals.read().await;
+
+    let buckets: HashMap<&str, &'static str> = tokens
+        .buckets()
+        .flat_map(|(bucket, tokens)| tokens.iter().map(move |token| (token.as_str(), bucket)))
+        .collect();
+
+    let results = matches
+        .into_iter()
+        .filter_map(|(token, score)| {
+            let status = *buckets.get(token.as_str())?;
+            if matches!(&query.status, Some(filter) if filter != status) {
+                return None;
+            }
+
+            let proposal = proposals.get(&token)?;
+            Some(types::SearchHit {
+                score,
+                status: status.to_string(),
+                proposal: types::ProposalSummary::from(proposal),
+            })
+        })
+        .take(limit)
+        .collect();
+
+    drop(proposals);
+    drop(tokens);
+
+    let search_result = types::SearchResult {
+        query: query.q.clone(),
+        results,
+    };
+
+    match serde_json::to_string(&search_result) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling search result struct, error: {}", e);
+
+            "error sending search results"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Returns an RFP and its submissions, the token may be the one of the RFP or
+/// of any of its submissions.
+#[get("/api/v1/rfp/{token}")]
+async fn rfp_submissions(
+    token: web::Path<String>,
+    policy: web::Data<Arc<RwLock<v1types::Policy>>>,
+    proposals: web::Data<Arc<RwLock<HashMap<String, v1types::Proposal>>>>,
+    vote_summaries: web::Data<Arc<RwLock<v1types::BatchVoteSummaryResult>>>,
+    versions: web::Data<Arc<RwLock<VersionHistory>>>,
+    rfps: web::Data<Arc<RwLock<RfpIndex>>>,
+) -> impl Responder {
+    let prefix_length = policy.read().await.token_prefix_length;
+    let proposals = proposals.read().await;
+
+    let token = match resolve_token(&proposals, &token, prefix_length) {
+        Ok(e) => e,
+
+        Err(e) => {
+            return resolve_error_body(&token, e).with_status(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let proposal = &proposals[&token];
+    let parent = if proposal.link_by != 0 {
+        proposal
+    } else {
+        match proposals.get(&proposal.link_to) {
+            Some(e) => e,
+
+            None => {
+                return error_body(ErrorCode::StatusWrongProposalType)
+                    .with_status(StatusCode::BAD_REQUEST);
+            }
+        }
+    };
+
+    let parent_token = &parent.censorship_record.token;
+    let vote_summaries = vote_summaries.read().await;
+    let versions = versions.read().await;
+    let rfps = rfps.read().await;
+
+    let result = types::RfpResult {
+        rfp: types::ProposalSummary::from(parent),
+        vote: vote_summaries.summaries.get(parent_token).cloned(),
+        submissions: rfps
+            .get(parent_token)
+            .into_iter()
+            .flatten()
+            .filter_map(|token| proposals.get(token))
+            .map(|submission| types::RfpSubmission {
+                proposal: types::ProposalSummary::from(submission),
+                past_deadline: rfp::past_deadline(parent, rfp::submitted_at(submission, &versions)),
+                vote: vote_summaries
+                    .summaries
+                    .get(&submission.censorship_record.token)
+                    .cloned(),
+            })
+            .collect(),
+    };
+
+    drop(rfps);
+    drop(versions);
+    drop(vote_summaries);
+    drop(proposals);
+
+    match serde_json::to_string(&result) {
+        Ok(e) => e.with_status(StatusCode::OK),
+
+        Err(e) => {
+            log::error!("Error marshalling rfp result struct, error: {}", e);
+
+            "error sending rfp"
+                .to_string()
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use politeia_api::v1::types;
+use politeia_api::v1::{
+    enum_values::{ProposalState, ProposalStatus},
+    types,
+};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Default, Eq, PartialEq, Clone)]
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
 pub struct Proposals {
     pub pre: types::ProposalsResult,
     pub abandoned: types::ProposalsResult,
@@ -11,7 +15,250 @@ pub struct Proposals {
     pub rejected: types::ProposalsResult,
 }
 
+impl Proposals {
+    /// Proposals of every inventory bucket.
+    pub fn buckets(&self) -> [&types::ProposalsResult; 7] {
+        [
+            &self.pre,
+            &self.abandoned,
+            &self.active,
+            &self.approved,
+            &self.censored,
+            &self.unreviewed,
+            &self.rejected,
+        ]
+    }
+
+    /// Returns the proposals of the named inventory bucket for modification.
+    pub fn bucket_mut(&mut self, name: &str) -> Option<&mut types::ProposalsResult> {
+        match name {
+            "pre" => Some(&mut self.pre),
+            "active" => Some(&mut self.active),
+            "approved" => Some(&mut self.approved),
+            "rejected" => Some(&mut self.rejected),
+            "abandoned" => Some(&mut self.abandoned),
+            "unreviewed" => Some(&mut self.unreviewed),
+            "censored" => Some(&mut self.censored),
+            _ => None,
+        }
+    }
+
+    /// Mutable access to the proposals of every inventory bucket.
+    pub fn buckets_mut(&mut self) -> [&mut types::ProposalsResult; 7] {
+        [
+            &mut self.pre,
+            &mut self.abandoned,
+            &mut self.active,
+            &mut self.approved,
+            &mut self.censored,
+            &mut self.unreviewed,
+            &mut self.rejected,
+        ]
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 pub struct Tokens {
     pub tokens: Vec<String>,
 }
+
+/// Query parameters of the proposal listing route.
+#[derive(serde::Deserialize, Debug)]
+pub struct ListQuery {
+    /// Inventory bucket to list, e.g `active`.
+    pub status: String,
+    /// Page number, starting at 1.
+    pub page: Option<usize>,
+    /// Proposals per page.
+    pub per_page: Option<usize>,
+}
+
+/// File description without its payload.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct FileSummary {
+    pub name: String,
+    pub mime: String,
+    pub digest: String,
+}
+
+impl From<&types::File> for FileSummary {
+    fn from(file: &types::File) -> Self {
+        FileSummary {
+            name: file.name.clone(),
+            mime: file.mime.clone(),
+            digest: file.digest.clone(),
+        }
+    }
+}
+
+/// Proposal without its file payloads and metadata.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ProposalSummary {
+    pub name: String,
+    pub state: ProposalState,
+    pub status: ProposalStatus,
+    pub timestamp: i64,
+    #[serde(rename = "userid")]
+    pub user_id: String,
+    pub username: String,
+    #[serde(rename = "numcomments")]
+    pub number_of_comments: usize,
+    pub version: String,
+    #[serde(rename = "publishedat")]
+    pub published_at: i64,
+    #[serde(rename = "censoredat")]
+    pub censored_at: i64,
+    #[serde(rename = "abandonedat")]
+    pub abandoned_at: i64,
+    #[serde(rename = "linkto")]
+    pub link_to: String,
+    #[serde(rename = "linkby")]
+    pub link_by: i64,
+    pub files: Vec<FileSummary>,
+    #[serde(rename = "censorshiprecord")]
+    pub censorship_record: types::CensorshipRecord,
+}
+
+impl From<&types::Proposal> for ProposalSummary {
+    fn from(proposal: &types::Proposal) -> Self {
+        ProposalSummary {
+            name: proposal.name.clone(),
+            state: proposal.proposal_state,
+            status: proposal.proposal_status,
+            timestamp: proposal.timestamp,
+            user_id: proposal.user_id.clone(),
+            username: proposal.username.clone(),
+            number_of_comments: proposal.number_of_comments,
+            version: proposal.version.clone(),
+            published_at: proposal.pubished_at,
+            censored_at: proposal.censored_at,
+            abandoned_at: proposal.abandoned_at,
+            link_to: proposal.link_to.clone(),
+            link_by: proposal.link_by,
+            files: proposal.files.iter().map(FileSummary::from).collect(),
+            censorship_record: proposal.censorship_record.clone(),
+        }
+    }
+}
+
+/// A page of proposal summaries of one inventory bucket.
+#[derive(serde::Serialize, Debug)]
+pub struct ProposalPage {
+    pub status: String,
+    pub page: usize,
+    pub per_page: usize,
+    /// Number of proposals in the bucket.
+    pub total: usize,
+    pub proposals: Vec<ProposalSummary>,
+}
+
+/// Query parameters of the search route.
+#[derive(serde::Deserialize, Debug)]
+pub struct SearchQuery {
+    /// Search terms.
+    pub q: String,
+    /// Restricts results to an inventory bucket, e.g `active`.
+    pub status: Option<String>,
+    /// Maximum number of results.
+    pub limit: Option<usize>,
+}
+
+/// A proposal matching a search query.
+#[derive(serde::Serialize, Debug)]
+pub struct SearchHit {
+    pub score: f64,
+    /// Inventory bucket of the proposal.
+    pub status: String,
+    pub proposal: ProposalSummary,
+}
+
+/// Ranked search results.
+#[derive(serde::Serialize, Debug)]
+pub struct SearchResult {
+    pub query: String,
+    pub results: Vec<SearchHit>,
+}
+
+/// Query parameters of the proposal diff route.
+#[derive(serde::Deserialize, Debug)]
+pub struct DiffQuery {
+    /// Old version.
+    pub from: u32,
+    /// New version.
+    pub to: u32,
+}
+
+/// A comment along with its replies.
+#[derive(serde::Serialize, Debug)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: types::Comment,
+    pub children: Vec<CommentNode>,
+}
+
+/// Threaded comments of a proposal.
+#[derive(serde::Serialize, Debug)]
+pub struct CommentThread {
+    pub token: String,
+    #[serde(rename = "numcomments")]
+    pub number_of_comments: usize,
+    pub comments: Vec<CommentNode>,
+}
+
+impl CommentThread {
+    /// Builds comment trees from a flat comment list, comments whose parent
+    /// is unknown are kept at the top level. Siblings keep their order in
+    /// `comments`.
+    pub fn new(token: String, comments: &[types::Comment]) -> CommentThread {
+        let ids: HashSet<&str> = comments.iter().map(|c| c.comment_id.as_str()).collect();
+
+        let mut children: HashMap<&str, Vec<&types::Comment>> = HashMap::new();
+        let mut roots = Vec::new();
+        for comment in comments {
+            if comment.parent_id != comment.comment_id && ids.contains(comment.parent_id.as_str()) {
+                children
+                    .entry(comment.parent_id.as_str())
+                    .or_default()
+                    .push(comment);
+            } else {
+                roots.push(comment);
+            }
+        }
+
+        fn build(
+            comment: &types::Comment,
+            children: &HashMap<&str, Vec<&types::Comment>>,
+        ) -> CommentNode {
+            CommentNode {
+                comment: comment.clone(),
+                children: children
+                    .get(comment.comment_id.as_str())
+                    .map(|replies| replies.iter().map(|c| build(c, children)).collect())
+                    .unwrap_or_default(),
+            }
+        }
+
+        CommentThread {
+            token,
+            number_of_comments: comments.len(),
+            comments: roots.into_iter().map(|c| build(c, &children)).collect(),
+        }
+    }
+}
+
+/// A proposal submitted to an RFP.
+#[derive(serde::Serialize, Debug)]
+pub struct RfpSubmission {
+    pub proposal: ProposalSummary,
+    /// Submission was submitted after the RFP `linkby` deadline.
+    pub past_deadline: bool,
+    pub vote: Option<types::VoteSummary>,
+}
+
+/// An RFP along with every proposal submitted to it.
+#[derive(serde::Serialize, Debug)]
+pub struct RfpResult {
+    pub rfp: ProposalSummary,
+    pub vote: Option<types::VoteSummary>,
+    pub submissions: Vec<RfpSubmission>,
+}
@@ -0,0 +1,258 @@
+use super::events::ChangeEvent;
+use hmac::{Hmac, Mac, NewMac};
+use std::{collections::VecDeque, sync::Arc};
+use tokio::{
+    sync::{broadcast, RwLock},
+    time,
+};
+
+/// Header carrying the hex encoded HMAC-SHA256 of the timestamp and payload,
+/// see [sign].
+pub const SIGNATURE_HEADER: &str = "X-Politeia-Signature";
+/// Header carrying the UNIX timestamp of the delivery attempt, receivers
+/// should refuse stale timestamps to prevent replays.
+pub const TIMESTAMP_HEADER: &str = "X-Politeia-Timestamp";
+/// Header carrying the event name.
+pub const EVENT_HEADER: &str = "X-Politeia-Event";
+/// Header carrying the random delivery id, identical across retries and
+/// unique across restarts.
+pub const DELIVERY_HEADER: &str = "X-Politeia-Delivery";
+
+/// Attempts made to deliver a payload before giving up.
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+/// Delay before the first retry, doubled after each failed attempt.
+const INITIAL_RETRY_DELAY_SECS: u64 = 1;
+/// Seconds a single delivery attempt may take.
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+/// Deliveries kept in the delivery log.
+const MAX_LOGGED_DELIVERIES: usize = 100;
+
+/// Body posted to webhook targets.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct Payload {
+    /// Random hex encoded id, receivers may deduplicate on it.
+    pub id: String,
+    pub timestamp: u64,
+    pub event: ChangeEvent,
+}
+
+/// Outcome of delivering a payload to a webhook target.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct Delivery {
+    pub id: String,
+    /// Host of the target url, urls may embed credentials and are not
+    /// exposed.
+    pub target: String,
+    pub event: String,
+    pub token: String,
+    pub attempts: u32,
+    pub delivered: bool,
+    /// HTTP status of the last attempt, if a response was received.
+    pub status: Option<u16>,
+    /// Error of the last failed attempt.
+    pub error: Option<String>,
+    /// UNIX timestamp of the last attempt.
+    pub timestamp: u64,
+}
+
+/// Most recent webhook deliveries, oldest first.
+#[derive(serde::Serialize, Default, Debug, Clone)]
+pub struct DeliveryLog {
+    pub deliveries: VecDeque<Delivery>,
+}
+
+impl DeliveryLog {
+    pub fn record(&mut self, delivery: Delivery) {
+        if self.deliveries.len() == MAX_LOGGED_DELIVERIES {
+            self.deliveries.pop_front();
+        }
+
+        self.deliveries.push_back(delivery);
+    }
+}
+
+/// Posts lifecycle change events to webhook targets.
+pub struct Webhooks {
+    client: reqwest::Client,
+    targets: Vec<String>,
+    secret: String,
+    log: Arc<RwLock<DeliveryLog>>,
+}
+
+impl Webhooks {
+    pub fn new(
+        targets: Vec<String>,
+        secret: String,
+        log: Arc<RwLock<DeliveryLog>>,
+    ) -> Result<Webhooks, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(time::Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Webhooks {
+            client,
+            targets,
+            secret,
+            log,
+        })
+    }
+
+    /// Delivers the lifecycle events received until the channel closes,
+    /// every target is delivered to concurrently.
+    pub async fn run(self, mut events: broadcast::Receiver<ChangeEvent>) {
+        let webhooks = Arc::new(self);
+
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+
+                Err(broadcast::RecvError::Lagged(skipped)) => {
+                    log::warn!("Webhooks skipped {} change events", skipped);
+                    continue;
+                }
+
+                Err(broadcast::RecvError::Closed) => return,
+            };
+
+            if !is_lifecycle(&event) {
+                continue;
+            }
+
+            let payload = Payload {
+                id: delivery_id(),
+                timestamp: unix_now(),
+                event,
+            };
+
+            for url in &webhooks.targets {
+                tokio::spawn(webhooks.clone().deliver(url.clone(), payload.clone()));
+            }
+        }
+    }
+
+    /// Posts a payload to a target, retrying failed attempts with
+    /// exponential backoff, and records the outcome in the delivery log.
+    pub async fn deliver(self: Arc<Self>, url: String, payload: Payload) {
+        let body = match serde_json::to_vec(&payload) {
+            Ok(e) => e,
+
+            Err(e) => {
+                log::error!("Error marshalling webhook payload struct, error: {}", e);
+                return;
+            }
+        };
+
+        let mut delivery = Delivery {
+            id: payload.id.clone(),
+            target: redact_url(&url),
+            event: payload.event.name().to_string(),
+            token: payload.event.token().to_string(),
+            attempts: 0,
+            delivered: false,
+            status: None,
+            error: None,
+            timestamp: 0,
+        };
+
+        let mut delay = time::Duration::from_secs(INITIAL_RETRY_DELAY_SECS);
+        while delivery.attempts < MAX_DELIVERY_ATTEMPTS {
+            if delivery.attempts > 0 {
+                time::delay_for(delay).await;
+                delay *= 2;
+            }
+
+            delivery.attempts += 1;
+            delivery.timestamp = unix_now();
+
+            let request = self
+                .client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, &delivery.event)
+                .header(DELIVERY_HEADER, &delivery.id)
+                .header(TIMESTAMP_HEADER, delivery.timestamp.to_string())
+                .header(
+                    SIGNATURE_HEADER,
+                    sign(&self.secret, delivery.timestamp, &body),
+                );
+
+            match request.body(body.clone()).send().await {
+                Ok(response) => {
+                    delivery.status = Some(response.status().as_u16());
+                    if response.status().is_success() {
+                        delivery.delivered = true;
+                        delivery.error = None;
+                        break;
+                    }
+
+                    delivery.error = Some(format!("unexpected status {}", response.status()));
+                }
+
+                Err(e) => {
+                    delivery.status = None;
+                    delivery.error = Some(e.to_string());
+                }
+            }
+        }
+
+        if !delivery.delivered {
+            log::error!(
+                "Error delivering webhook {} to {} after {} attempts, error: {}",
+                delivery.id,
+                delivery.target,
+                delivery.attempts,
+                delivery.error.as_deref().unwrap_or_default()
+            );
+        }
+
+        self.log.write().await.record(delivery);
+    }
+}
+
+/// Signs `<timestamp>.<body>` with HMAC-SHA256, formatted as
+/// `sha256=<hex digest>`. Covering the timestamp lets receivers refuse
+/// replayed deliveries.
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Reduces a target url to its host and port, paths and queries of webhook
+/// urls often carry secrets.
+pub fn redact_url(url: &str) -> String {
+    let url = match reqwest::Url::parse(url) {
+        Ok(e) => e,
+        Err(_) => return "invalid url".to_string(),
+    };
+
+    match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => "invalid url".to_string(),
+    }
+}
+
+/// Reports if an event is a proposal lifecycle transition, a proposal
+/// entering the inventory or moving between inventory buckets.
+fn is_lifecycle(event: &ChangeEvent) -> bool {
+    matches!(
+        event,
+        ChangeEvent::NewProposal { .. } | ChangeEvent::StatusChange { .. }
+    )
+}
+
+/// Random 128 bit id, hex encoded.
+fn delivery_id() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::proposal;
+use politeia::cache::{resolve_token, ResolveError};
+use politeia_api::v1::types;
+use std::collections::HashMap;
+
+const TOKEN: &str = "2b2d5d2c4ae3c7bd5b1b0e7a3b0e2d5c8f1c3d4e5f60718293a4b5c6d7e8f901";
+
+fn proposals(tokens: &[&str]) -> HashMap<String, types::Proposal> {
+    tokens
+        .iter()
+        .map(|token| {
+            (
+                token.to_string(),
+                proposal(token, "Proposal", "alice", "Body."),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn resolve_token_accepts_full_tokens_and_unique_prefixes() {
+    let proposals = proposals(&[TOKEN, "2b2d5e0000000000", "9c1f"]);
+
+    assert_eq!(resolve_token(&proposals, TOKEN, 7).unwrap(), TOKEN);
+    assert_eq!(resolve_token(&proposals, "2b2d5d2", 7).unwrap(), TOKEN);
+    // Cached tokens shorter than the prefix length are matched in full.
+    assert_eq!(resolve_token(&proposals, "9c1f", 7).unwrap(), "9c1f");
+}
+
+#[test]
+fn resolve_token_refuses_short_unknown_and_ambiguous_prefixes() {
+    let proposals = proposals(&[TOKEN, "2b2d5d2c00000000"]);
+
+    assert_eq!(
+        resolve_token(&proposals, "2b2d5d", 7),
+        Err(ResolveError::TooShort(7))
+    );
+    assert_eq!(
+        resolve_token(&proposals, "ffffffff", 7),
+        Err(ResolveError::NotFound)
+    );
+    assert_eq!(
+        resolve_token(&proposals, "2b2d5d2", 7),
+        Err(ResolveError::Ambiguous(vec![
+            "2b2d5d2c00000000".to_string(),
+            TOKEN.to_string()
+        ]))
+    );
+    assert_eq!(
+        resolve_token(&proposals, &TOKEN[..9], 7).unwrap(),
+        TOKEN,
+        "longer prefixes disambiguate"
+    );
+}
@@ -0,0 +1,345 @@
+mod common;
+
+use common::{Fault, MockPoliteia};
+use politeia::{
+    error::ClientError,
+    model::Client,
+    retry::{self, RetryPolicy},
+};
+use politeia_api::v1::errors::ErrorCode;
+use reqwest::StatusCode;
+use std::time::{Duration, Instant, SystemTime};
+
+const UNAVAILABLE: Fault = Fault {
+    status: 503,
+    retry_after: None,
+};
+
+fn client(mock: &MockPoliteia) -> Client {
+    Client::builder()
+        .host(mock.host())
+        .retry_policy(common::fast_retries())
+        .build()
+        .unwrap()
+}
+
+fn tokens_of(proposals: &politeia_api::v1::types::ProposalsResult) -> Vec<String> {
+    proposals
+        .proposals
+        .iter()
+        .map(|proposal| proposal.censorship_record.token.clone())
+        .collect()
+}
+
+fn sorted_tokens(proposals: &politeia_api::v1::types::ProposalsResult) -> Vec<String> {
+    let mut tokens = tokens_of(proposals);
+    tokens.sort();
+    tokens
+}
+
+#[tokio::test]
+async fn fetch_all_proposals_fills_every_bucket() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+
+    let inventory = client.fetch_tokens().await.unwrap();
+    let report = client.fetch_all_proposals(inventory.clone()).await.unwrap();
+    assert!(report.is_complete());
+    let proposals = report.proposals;
+
+    let mut expected = inventory.pre.clone();
+    expected.sort();
+    assert_eq!(sorted_tokens(&proposals.pre), expected);
+    assert_eq!(sorted_tokens(&proposals.active), inventory.active);
+    assert_eq!(sorted_tokens(&proposals.approved), inventory.approved);
+    assert_eq!(sorted_tokens(&proposals.abandoned), inventory.abandoned);
+    assert!(proposals.rejected.proposals.is_empty());
+    assert!(proposals.unreviewed.proposals.is_empty());
+    assert!(proposals.censored.proposals.is_empty());
+
+    let backend_public_key = &mock.state().policy.backend_public_key;
+    for bucket in proposals.buckets().iter() {
+        for proposal in &bucket.proposals {
+            proposal.verify(backend_public_key).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn fetch_all_proposals_batches_by_page_size_in_inventory_order() {
+    let mock = MockPoliteia::start();
+    let tokens: Vec<String> = mock.state().inventory.all_tokens().cloned().collect();
+    for token in &tokens {
+        mock.move_token(token, "approved");
+    }
+    mock.state().reverse_batches = true;
+
+    let mut client = Client::builder()
+        .host(mock.host())
+        .concurrency(2)
+        .build()
+        .unwrap();
+
+    // The policy is unknown until fetched, its page size of two applies.
+    let inventory = client.fetch_tokens().await.unwrap();
+    let report = client.fetch_all_proposals(inventory.clone()).await.unwrap();
+    assert!(report.is_complete());
+    let proposals = report.proposals;
+
+    let fetched: Vec<&String> = proposals
+        .approved
+        .proposals
+        .iter()
+        .map(|proposal| &proposal.censorship_record.token)
+        .collect();
+    assert_eq!(fetched, inventory.approved.iter().collect::<Vec<_>>());
+    assert_eq!(mock.hits("policy"), 1);
+    assert_eq!(mock.hits("batch"), 2);
+    assert_eq!(mock.hits("version"), 1);
+}
+
+#[tokio::test]
+async fn fetch_all_proposals_keeps_partial_results() {
+    let mock = MockPoliteia::start();
+    let tokens: Vec<String> = mock.state().inventory.all_tokens().cloned().collect();
+    for token in &tokens {
+        mock.move_token(token, "approved");
+    }
+    let failing = mock.state().inventory.approved[3].clone();
+    mock.state().failing_tokens.insert(failing.clone());
+
+    let mut client = client(&mock);
+    let inventory = client.fetch_tokens().await.unwrap();
+    let report = client.fetch_all_proposals(inventory.clone()).await.unwrap();
+
+    // The second batch of two tokens failed, the first was kept.
+    assert!(!report.is_complete());
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].bucket, "approved");
+    assert_eq!(
+        report.failed[0].error.status(),
+        Some(StatusCode::INTERNAL_SERVER_ERROR)
+    );
+    assert_eq!(
+        report.failed_tokens().collect::<Vec<_>>(),
+        inventory.approved[2..].iter().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        tokens_of(&report.proposals.approved),
+        inventory.approved[..2]
+    );
+}
+
+#[tokio::test]
+async fn csrf_token_is_reused_then_renewed_when_rejected() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+    let token = mock.state().inventory.active[0].clone();
+
+    client
+        .fetch_batch_proposal(vec![token.clone()])
+        .await
+        .unwrap();
+    client
+        .fetch_batch_proposal(vec![token.clone()])
+        .await
+        .unwrap();
+    assert_eq!(mock.hits("version"), 1);
+
+    mock.rotate_csrf();
+
+    let result = client.fetch_batch_proposal(vec![token]).await.unwrap();
+    assert_eq!(result.proposals.len(), 1);
+    assert_eq!(mock.hits("version"), 2);
+    assert_eq!(mock.hits("batch"), 4);
+}
+
+#[tokio::test]
+async fn user_errors_are_decoded() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+
+    let tokens = mock.state().inventory.all_tokens().cloned().collect();
+    let err = client.fetch_batch_proposal(tokens).await.unwrap_err();
+
+    assert_eq!(
+        err.error_code(),
+        Some(ErrorCode::StatusMaxProposalsExceededPolicy)
+    );
+    assert!(!err.is_transient());
+    assert!(matches!(err, ClientError::Api { .. }));
+}
+
+#[tokio::test]
+async fn fetch_vote_summaries_of_vetted_proposals() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+
+    let inventory = client.fetch_tokens().await.unwrap();
+    let report = client.fetch_vote_summaries(&inventory).await.unwrap();
+
+    assert!(report.failed.is_empty());
+    assert_eq!(report.summaries, mock.state().vote_summaries);
+    // Three vetted proposals, each in its own bucket.
+    assert_eq!(mock.hits("batchvotesummary"), 3);
+}
+
+#[tokio::test]
+async fn fetch_vote_summaries_keeps_partial_results() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+    let active = mock.state().inventory.active[0].clone();
+    mock.state().failing_summaries.insert(active.clone());
+
+    let inventory = client.fetch_tokens().await.unwrap();
+    let report = client.fetch_vote_summaries(&inventory).await.unwrap();
+
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].bucket, "active");
+    assert_eq!(report.failed[0].tokens, vec![active.clone()]);
+    assert_eq!(
+        report.failed[0].error.status(),
+        Some(StatusCode::INTERNAL_SERVER_ERROR)
+    );
+
+    let mut expected = mock.state().vote_summaries.clone();
+    expected.summaries.remove(&active);
+    assert_eq!(report.summaries, expected);
+}
+
+#[tokio::test]
+async fn transient_errors_are_retried() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+    let token = mock.state().inventory.active[0].clone();
+
+    mock.fail_next(&[
+        UNAVAILABLE,
+        Fault {
+            status: 502,
+            retry_after: None,
+        },
+    ]);
+    let result = client.fetch_batch_proposal(vec![token]).await.unwrap();
+
+    assert_eq!(result.proposals.len(), 1);
+    // Faults hit both the CSRF token request and the batch, both retried.
+    assert!(mock.state().faults.is_empty());
+    assert_eq!(mock.hits("batch"), 1);
+}
+
+#[tokio::test]
+async fn rate_limited_requests_wait_for_retry_after() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+
+    mock.fail_next(&[Fault {
+        status: 429,
+        retry_after: Some("1"),
+    }]);
+    let started = Instant::now();
+    client.fetch_tokens().await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(mock.hits("tokeninventory"), 1);
+}
+
+#[tokio::test]
+async fn retries_give_up_after_max_retries() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+
+    mock.fail_next(&[UNAVAILABLE; 5]);
+    let err = client.fetch_tokens().await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        ClientError::RateLimited {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            retry_after: None,
+        }
+    ));
+    // One attempt and three retries, the last fault is left unused.
+    assert_eq!(mock.state().faults.len(), 1);
+}
+
+#[tokio::test]
+async fn retry_after_beyond_max_backoff_waits_max_backoff() {
+    let mock = MockPoliteia::start();
+    let client = Client::builder()
+        .host(mock.host())
+        .retry_policy(RetryPolicy {
+            max_backoff: Duration::from_secs(1),
+            ..common::fast_retries()
+        })
+        .build()
+        .unwrap();
+
+    mock.fail_next(&[Fault {
+        status: 429,
+        retry_after: Some("3600"),
+    }]);
+    let started = Instant::now();
+    client.fetch_tokens().await.unwrap();
+
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_secs(1));
+    assert!(elapsed < Duration::from_secs(10));
+    assert_eq!(mock.hits("tokeninventory"), 1);
+}
+
+#[tokio::test]
+async fn retry_after_dates_in_the_past_retry_immediately() {
+    let mock = MockPoliteia::start();
+    let client = client(&mock);
+
+    mock.fail_next(&[Fault {
+        status: 503,
+        retry_after: Some("Sun, 06 Nov 1994 08:49:37 GMT"),
+    }]);
+    client.fetch_tokens().await.unwrap();
+
+    assert!(mock.state().faults.is_empty());
+    assert_eq!(mock.hits("tokeninventory"), 1);
+}
+
+#[test]
+fn retry_after_is_parsed_as_seconds_or_http_date() {
+    assert_eq!(
+        retry::parse_retry_after(" 120 "),
+        Some(Duration::from_secs(120))
+    );
+
+    let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+    let delay = retry::parse_retry_after(&date).unwrap();
+    assert!(delay > Duration::from_secs(110) && delay <= Duration::from_secs(120));
+
+    // Obsolete RFC 850 and asctime forms are accepted too.
+    assert_eq!(
+        retry::parse_retry_after("Sunday, 06-Nov-94 08:49:37 GMT"),
+        Some(Duration::from_secs(0))
+    );
+    assert_eq!(
+        retry::parse_retry_after("Sun Nov  6 08:49:37 1994"),
+        Some(Duration::from_secs(0))
+    );
+
+    assert_eq!(retry::parse_retry_after("soon"), None);
+    assert_eq!(retry::parse_retry_after("-1"), None);
+}
+
+#[tokio::test]
+async fn disabled_retries_fail_on_the_first_error() {
+    let mock = MockPoliteia::start();
+    let client = Client::builder()
+        .host(mock.host())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    mock.fail_next(&[UNAVAILABLE, UNAVAILABLE]);
+    let err = client.fetch_tokens().await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(mock.state().faults.len(), 1);
+}
@@ -0,0 +1,68 @@
+use politeia::types::{CommentNode, CommentThread};
+use politeia_api::v1::types::Comment;
+
+fn comment(id: &str, parent: &str) -> Comment {
+    Comment {
+        token: "3bd320fb".to_string(),
+        parent_id: parent.to_string(),
+        comment_id: id.to_string(),
+        comment: format!("Comment {}", id),
+        ..Default::default()
+    }
+}
+
+/// Comment ids of `nodes` and their replies, replies follow their parent in
+/// brackets.
+fn shape(nodes: &[CommentNode]) -> String {
+    nodes
+        .iter()
+        .map(|node| {
+            if node.children.is_empty() {
+                node.comment.comment_id.clone()
+            } else {
+                format!("{}[{}]", node.comment.comment_id, shape(&node.children))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn comment_thread_nests_replies() {
+    let comments = vec![
+        comment("1", "0"),
+        comment("2", "1"),
+        comment("3", "2"),
+        comment("4", "0"),
+        comment("5", "1"),
+    ];
+    let thread = CommentThread::new("3bd320fb".to_string(), &comments);
+
+    assert_eq!(thread.token, "3bd320fb");
+    assert_eq!(thread.number_of_comments, 5);
+    assert_eq!(shape(&thread.comments), "1[2[3] 5] 4");
+}
+
+#[test]
+fn comment_thread_keeps_orphans_at_the_top_level() {
+    let comments = vec![comment("1", "0"), comment("2", "9"), comment("3", "3")];
+    let thread = CommentThread::new("3bd320fb".to_string(), &comments);
+
+    assert_eq!(shape(&thread.comments), "1 2 3");
+    assert_eq!(thread.number_of_comments, 3);
+}
+
+#[test]
+fn comment_thread_keeps_sibling_order() {
+    // Replies listed before their parent and interleaved with other threads.
+    let comments = vec![
+        comment("4", "1"),
+        comment("1", "0"),
+        comment("2", "0"),
+        comment("5", "2"),
+        comment("3", "1"),
+    ];
+    let thread = CommentThread::new("3bd320fb".to_string(), &comments);
+
+    assert_eq!(shape(&thread.comments), "1[4 3] 2[5]");
+}
@@ -0,0 +1,428 @@
+//! In-process mock of the politeiawww v1 API serving fixture data.
+
+#![allow(dead_code)]
+
+use actix_web::{dev::Service, web, App, HttpResponse, HttpServer};
+use futures::future::{self, Either};
+use politeia::retry::RetryPolicy;
+use politeia_api::v1::{errors::ErrorCode, routes, types};
+use sha2::Digest;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::TcpListener,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+const CSRF_TOKEN: &str = "X-CSRF-Token";
+
+/// Reads a fixture from `tests/fixtures`.
+pub fn fixture<T: serde::de::DeserializeOwned>(name: &str) -> T {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let data = std::fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path, e));
+    serde_json::from_slice(&data).unwrap_or_else(|e| panic!("decoding {}: {}", path, e))
+}
+
+/// Proposal with a verifiable `index.md` holding `body`, it carries no
+/// censorship record signature.
+pub fn proposal(token: &str, name: &str, username: &str, body: &str) -> types::Proposal {
+    let mut proposal = types::Proposal {
+        name: name.to_string(),
+        username: username.to_string(),
+        version: "1".to_string(),
+        files: vec![file("index.md", body)],
+        ..Default::default()
+    };
+    proposal.censorship_record.token = token.to_string();
+    proposal
+}
+
+/// Proposal file holding `body` along with its digest.
+pub fn file(name: &str, body: &str) -> types::File {
+    types::File {
+        name: name.to_string(),
+        mime: "text/plain; charset=utf-8".to_string(),
+        digest: hex::encode(sha2::Sha256::digest(body.as_bytes())),
+        payload: base64::encode(body),
+    }
+}
+
+/// Retry policy with short backoffs, keeping tests of failures fast.
+pub fn fast_retries() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_secs(2),
+    }
+}
+
+/// Error response returned in place of the next request.
+#[derive(Debug, Clone)]
+pub struct Fault {
+    pub status: u16,
+    /// Value of the `Retry-After` header.
+    pub retry_after: Option<&'static str>,
+}
+
+/// Data served by the mock, tests modify it to simulate politeiawww changes.
+pub struct State {
+    pub version: serde_json::Value,
+    pub policy: types::Policy,
+    pub inventory: types::TokenInventory,
+    pub proposals: HashMap<String, types::Proposal>,
+    /// Prior proposal versions, keyed by token and version.
+    pub versions: HashMap<(String, u32), types::Proposal>,
+    pub vote_summaries: types::BatchVoteSummaryResult,
+    pub vote_status: serde_json::Value,
+    pub comments: HashMap<String, types::GetCommentsResult>,
+    /// CSRF token handed out by the version route and required on POSTs.
+    pub csrf_token: String,
+    csrf_generation: usize,
+    /// Requests received per route, keyed by route name.
+    pub hits: HashMap<&'static str, usize>,
+    /// Return batched proposals in reverse token order.
+    pub reverse_batches: bool,
+    /// Batches requesting any of these tokens fail with a server error.
+    pub failing_tokens: HashSet<String>,
+    /// Vote summary batches requesting any of these tokens fail with a
+    /// server error.
+    pub failing_summaries: HashSet<String>,
+    /// Comments of these tokens fail with a server error.
+    pub failing_comments: HashSet<String>,
+    /// Faults answering the next requests, whatever their route.
+    pub faults: VecDeque<Fault>,
+}
+
+impl State {
+    fn from_fixtures() -> State {
+        let proposals: types::ProposalsResult = fixture("proposals.json");
+
+        State {
+            version: fixture("version.json"),
+            policy: fixture("policy.json"),
+            inventory: fixture("tokeninventory.json"),
+            proposals: proposals
+                .proposals
+                .into_iter()
+                .map(|proposal| (proposal.censorship_record.token.clone(), proposal))
+                .collect(),
+            versions: HashMap::new(),
+            vote_summaries: fixture("batchvotesummary.json"),
+            vote_status: fixture("votestatus.json"),
+            comments: fixture("comments.json"),
+            csrf_token: "csrf-0".to_string(),
+            csrf_generation: 0,
+            hits: HashMap::new(),
+            reverse_batches: false,
+            failing_tokens: HashSet::new(),
+            failing_summaries: HashSet::new(),
+            failing_comments: HashSet::new(),
+            faults: VecDeque::new(),
+        }
+    }
+
+    fn hit(&mut self, route: &'static str) {
+        *self.hits.entry(route).or_default() += 1;
+    }
+
+    /// Checks the CSRF header and the policy page size of a batch request,
+    /// returns the requested tokens.
+    fn batch_tokens(
+        &self,
+        request: &web::HttpRequest,
+        body: &[u8],
+    ) -> Result<Vec<String>, HttpResponse> {
+        let csrf = request
+            .headers()
+            .get(CSRF_TOKEN)
+            .and_then(|value| value.to_str().ok());
+        if csrf != Some(self.csrf_token.as_str()) {
+            return Err(HttpResponse::Forbidden().body("Forbidden - CSRF token invalid\n"));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Batch {
+            tokens: Vec<String>,
+        }
+
+        let batch: Batch = match serde_json::from_slice(body) {
+            Ok(e) => e,
+            Err(_) => return Err(user_error(ErrorCode::Invalid)),
+        };
+
+        if batch.tokens.len() > self.policy.proposal_list_page_size {
+            return Err(user_error(ErrorCode::StatusMaxProposalsExceededPolicy));
+        }
+
+        Ok(batch.tokens)
+    }
+}
+
+fn user_error(code: ErrorCode) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "errorcode": i64::from(code),
+        "errorcontext": [],
+    }))
+}
+
+fn fault_response(fault: &Fault) -> HttpResponse {
+    let status = actix_web::http::StatusCode::from_u16(fault.status).expect("invalid fault status");
+    let mut response = HttpResponse::build(status);
+    if let Some(retry_after) = fault.retry_after {
+        response.header("Retry-After", retry_after);
+    }
+
+    response.finish()
+}
+
+type Shared = web::Data<Arc<Mutex<State>>>;
+
+/// Mock politeiawww listening on a random local port.
+pub struct MockPoliteia {
+    host: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockPoliteia {
+    /// Starts the mock on its own actix system, serving the fixtures.
+    pub fn start() -> MockPoliteia {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding mock politeiawww");
+        let host = format!("http://{}/api", listener.local_addr().unwrap());
+
+        let state = Arc::new(Mutex::new(State::from_fixtures()));
+        let server_state = state.clone();
+
+        std::thread::spawn(move || {
+            let mut system = actix_web::rt::System::new("mock-politeiawww");
+
+            let server = HttpServer::new(move || {
+                let faults = server_state.clone();
+
+                App::new()
+                    .data(server_state.clone())
+                    .wrap_fn(move |request, service| {
+                        let fault = faults.lock().unwrap().faults.pop_front();
+                        match fault {
+                            Some(fault) => {
+                                let response = fault_response(&fault);
+                                Either::Left(future::ok(request.into_response(response)))
+                            }
+                            None => Either::Right(service.call(request)),
+                        }
+                    })
+                    .service(
+                        web::scope("/api")
+                            .route(routes::REQUEST_GET_VERSION, web::get().to(version))
+                            .route(routes::REQUEST_GET_POLICY, web::get().to(policy))
+                            .route(
+                                routes::REQUEST_GET_TOKEN_INVENTORY,
+                                web::get().to(token_inventory),
+                            )
+                            .route(routes::REQUEST_GET_VOTE_STATUS, web::get().to(vote_status))
+                            .route(routes::REQUEST_POST_BATCH_PROPOSALS, web::post().to(batch))
+                            .route(
+                                routes::REQUEST_POST_BATCH_VOTE_SUMMARY,
+                                web::post().to(batch_vote_summary),
+                            )
+                            .route(routes::REQUEST_GET_COMMENTS, web::get().to(comments))
+                            .route(
+                                routes::REQUEST_GET_PROPOSAL_DETAILS,
+                                web::get().to(proposal_details),
+                            ),
+                    )
+            })
+            .workers(1)
+            .listen(listener)
+            .expect("listening mock politeiawww")
+            .run();
+
+            system.block_on(server)
+        });
+
+        MockPoliteia { host, state }
+    }
+
+    /// Base url to build a [politeia::model::Client] against.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Number of requests received by a route.
+    pub fn hits(&self, route: &str) -> usize {
+        self.state().hits.get(route).copied().unwrap_or_default()
+    }
+
+    /// Replaces the CSRF token, as a politeiawww restart would, rejecting
+    /// requests signed with the previous one.
+    pub fn rotate_csrf(&self) {
+        let mut state = self.state();
+        state.csrf_generation += 1;
+        state.csrf_token = format!("csrf-{}", state.csrf_generation);
+    }
+
+    /// Answers the next requests with the given faults, in order.
+    pub fn fail_next(&self, faults: &[Fault]) {
+        self.state().faults.extend(faults.iter().cloned());
+    }
+
+    /// Moves a token to another inventory bucket.
+    pub fn move_token(&self, token: &str, bucket: &str) {
+        self.remove_token(token);
+
+        self.state()
+            .inventory
+            .bucket_mut(bucket)
+            .expect("unknown bucket")
+            .push(token.to_string());
+    }
+
+    /// Drops a token from the inventory, as politeiawww does for proposals
+    /// that are no longer public.
+    pub fn remove_token(&self, token: &str) {
+        let mut state = self.state();
+        for name in types::TokenInventory::BUCKETS.iter() {
+            state
+                .inventory
+                .bucket_mut(name)
+                .unwrap()
+                .retain(|t| t != token);
+        }
+    }
+}
+
+async fn version(state: Shared) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("version");
+
+    HttpResponse::Ok()
+        .header(CSRF_TOKEN, state.csrf_token.clone())
+        .json(&state.version)
+}
+
+async fn policy(state: Shared) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("policy");
+
+    HttpResponse::Ok().json(&state.policy)
+}
+
+async fn token_inventory(state: Shared) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("tokeninventory");
+
+    HttpResponse::Ok().json(&state.inventory)
+}
+
+async fn vote_status(state: Shared) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("votestatus");
+
+    HttpResponse::Ok().json(&state.vote_status)
+}
+
+async fn batch(state: Shared, request: web::HttpRequest, body: web::Bytes) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("batch");
+
+    let tokens = match state.batch_tokens(&request, &body) {
+        Ok(e) => e,
+        Err(e) => return e,
+    };
+
+    if tokens
+        .iter()
+        .any(|token| state.failing_tokens.contains(token))
+    {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let mut proposals: Vec<&types::Proposal> = tokens
+        .iter()
+        .filter_map(|token| state.proposals.get(token))
+        .collect();
+    if state.reverse_batches {
+        proposals.reverse();
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({ "proposals": proposals }))
+}
+
+async fn batch_vote_summary(
+    state: Shared,
+    request: web::HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("batchvotesummary");
+
+    let tokens = match state.batch_tokens(&request, &body) {
+        Ok(e) => e,
+        Err(e) => return e,
+    };
+
+    if tokens
+        .iter()
+        .any(|token| state.failing_summaries.contains(token))
+    {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let summaries: HashMap<&String, &types::VoteSummary> = tokens
+        .iter()
+        .filter_map(|token| {
+            state
+                .vote_summaries
+                .summaries
+                .get(token)
+                .map(|summary| (token, summary))
+        })
+        .collect();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "bestblock": state.vote_summaries.best_block,
+        "summaries": summaries,
+    }))
+}
+
+async fn comments(state: Shared, token: web::Path<String>) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("comments");
+    if state.failing_comments.contains(token.as_str()) {
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    match state.comments.get(token.as_str()) {
+        Some(e) => HttpResponse::Ok().json(e),
+        None => HttpResponse::Ok().json(types::GetCommentsResult::default()),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct DetailsQuery {
+    version: Option<u32>,
+}
+
+async fn proposal_details(
+    state: Shared,
+    token: web::Path<String>,
+    query: web::Query<DetailsQuery>,
+) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    state.hit("proposal");
+
+    if let Some(version) = query.version {
+        return match state.versions.get(&(token.into_inner(), version)) {
+            Some(e) => HttpResponse::Ok().json(serde_json::json!({ "proposal": e })),
+            None => user_error(ErrorCode::StatusInvalidProposalVersion),
+        };
+    }
+
+    match state.proposals.get(token.as_str()) {
+        Some(e) => HttpResponse::Ok().json(serde_json::json!({ "proposal": e })),
+        None => user_error(ErrorCode::StatusProposalNotFound),
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{file, proposal};
+use politeia::diff::ProposalDiff;
+
+const INDEX_FILENAME: &str = "index.md";
+
+#[test]
+fn diff_reports_name_changes() {
+    let old = proposal("aa", "Marketing", "alice", "Body.");
+    let new = proposal("aa", "Marketing 2021", "alice", "Body.");
+
+    let diff = ProposalDiff::new(&old, &new, 1, 2, INDEX_FILENAME);
+    let name = diff.name.unwrap();
+    assert_eq!(
+        (name.from.as_str(), name.to.as_str()),
+        ("Marketing", "Marketing 2021")
+    );
+    assert!(diff.index.unwrap().is_empty());
+
+    let unchanged = ProposalDiff::new(&old, &old, 1, 1, INDEX_FILENAME);
+    assert!(unchanged.name.is_none());
+}
+
+#[test]
+fn diff_reports_added_removed_and_modified_files() {
+    let mut old = proposal("aa", "Marketing", "alice", "Body.");
+    old.files.push(file("budget.csv", "item,cost\n"));
+    old.files.push(file("removed.txt", "Gone.\n"));
+
+    let mut new = proposal("aa", "Marketing", "alice", "Body.");
+    new.files.push(file("budget.csv", "item,cost\nads,100\n"));
+    new.files.push(file("added.txt", "New.\n"));
+
+    let files = ProposalDiff::new(&old, &new, 1, 2, INDEX_FILENAME).files;
+
+    let added: Vec<&str> = files.added.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(added, vec!["added.txt"]);
+    let removed: Vec<&str> = files.removed.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(removed, vec!["removed.txt"]);
+
+    assert_eq!(files.modified.len(), 1);
+    let modified = &files.modified[0];
+    assert_eq!(modified.name, "budget.csv");
+    assert_eq!(modified.from_digest, old.files[1].digest);
+    assert_eq!(modified.to_digest, new.files[1].digest);
+}
+
+#[test]
+fn diff_hunks_keep_context_around_index_changes() {
+    let old_body = "# Title\n1\n2\n3\n4\n5\n6\n7\n8\n9\nEnd\n";
+    let new_body = "# New title\n1\n2\n3\n4\n5\n6\n7\n8\n9\nEnd\n";
+    let old = proposal("aa", "Marketing", "alice", old_body);
+    let new = proposal("aa", "Marketing", "alice", new_body);
+
+    let diff = ProposalDiff::new(&old, &new, 1, 2, INDEX_FILENAME);
+    assert_eq!(diff.files.modified[0].name, "index.md");
+
+    let hunks = diff.index.unwrap();
+    assert_eq!(hunks.len(), 1);
+    let hunk = &hunks[0];
+    assert_eq!((hunk.old_start, hunk.old_lines), (1, 4));
+    assert_eq!((hunk.new_start, hunk.new_lines), (1, 4));
+
+    let lines: Vec<(&str, &str)> = hunk
+        .lines
+        .iter()
+        .map(|line| (line.op, line.text.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            ("delete", "# Title"),
+            ("insert", "# New title"),
+            ("equal", "1"),
+            ("equal", "2"),
+            ("equal", "3"),
+        ]
+    );
+}
+
+#[test]
+fn diff_skips_undecodable_index_files() {
+    let old = proposal("aa", "Marketing", "alice", "Body.");
+    let mut new = proposal("aa", "Marketing", "alice", "Body.");
+    new.files[0].payload = "VGFtcGVyZWQu".to_string();
+
+    assert!(ProposalDiff::new(&old, &new, 1, 2, INDEX_FILENAME)
+        .index
+        .is_none());
+}
@@ -0,0 +1,38 @@
+use politeia::render::markdown_to_html;
+
+#[test]
+fn raw_html_is_escaped() {
+    let html = markdown_to_html("<script>alert(1)</script>\n\nbody <img src=x onerror=alert(1)>");
+
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("<img"));
+}
+
+#[test]
+fn script_links_and_images_are_neutralized() {
+    let html = markdown_to_html(
+        "[click](javascript:alert(1)) [tab](<java\tscript:alert(1)>) \
+         [upper](JavaScript:alert(1)) ![img](data:image/svg+xml;base64,PHN2Zz4=)",
+    );
+
+    assert!(
+        !html.to_ascii_lowercase().contains("script:alert"),
+        "{}",
+        html
+    );
+    assert!(!html.contains("data:"), "{}", html);
+    assert_eq!(html.matches(r##"href="#""##).count(), 3, "{}", html);
+    assert!(html.contains(r##"<img src="#""##), "{}", html);
+}
+
+#[test]
+fn web_and_relative_links_are_kept() {
+    let html = markdown_to_html(
+        "[site](https://decred.org) [mail](mailto:a@b.c) [section](#budget) [file](./a.md)",
+    );
+
+    assert!(html.contains(r#"href="https://decred.org""#));
+    assert!(html.contains(r#"href="mailto:a@b.c""#));
+    assert!(html.contains(r##"href="#budget""##));
+    assert!(html.contains(r#"href="./a.md""#));
+}
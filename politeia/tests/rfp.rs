@@ -0,0 +1,98 @@
+mod common;
+
+use common::proposal;
+use politeia::{cache::VersionHistory, rfp};
+use politeia_api::v1::types::Proposal;
+use std::collections::HashMap;
+
+const LINK_BY: i64 = 1_600_000_000;
+
+fn rfp_proposal() -> Proposal {
+    let mut rfp = proposal("rfp", "Marketing RFP", "alice", "Submit proposals.");
+    rfp.link_by = LINK_BY;
+    rfp
+}
+
+fn submission(token: &str, timestamp: i64, published_at: i64) -> Proposal {
+    let mut submission = proposal(token, "Submission", "bob", "Proposal.");
+    submission.link_to = "rfp".to_string();
+    submission.timestamp = timestamp;
+    submission.pubished_at = published_at;
+    submission
+}
+
+#[test]
+fn build_index_links_submissions_by_publication_time() {
+    let proposals: HashMap<String, Proposal> = vec![
+        rfp_proposal(),
+        submission("bb", LINK_BY - 10, LINK_BY - 5),
+        submission("aa", LINK_BY - 20, LINK_BY - 1),
+        submission("cc", LINK_BY - 30, LINK_BY - 5),
+        proposal("dd", "Unrelated", "carol", "Proposal."),
+    ]
+    .into_iter()
+    .map(|proposal| (proposal.censorship_record.token.clone(), proposal))
+    .collect();
+
+    let index = rfp::build_index(&proposals);
+
+    assert_eq!(index.len(), 1);
+    assert_eq!(index["rfp"], vec!["bb", "cc", "aa"]);
+}
+
+#[test]
+fn submissions_before_the_deadline_are_on_time() {
+    let rfp = rfp_proposal();
+    let versions = VersionHistory::new();
+
+    let on_time = submission("aa", LINK_BY, LINK_BY);
+    assert!(!rfp::past_deadline(
+        &rfp,
+        rfp::submitted_at(&on_time, &versions)
+    ));
+
+    // Published by an admin once the deadline passed.
+    let reviewed_late = submission("bb", LINK_BY - 60, LINK_BY + 3600);
+    assert!(!rfp::past_deadline(
+        &rfp,
+        rfp::submitted_at(&reviewed_late, &versions)
+    ));
+
+    // Proposals without a deadline are never late.
+    let mut open = rfp_proposal();
+    open.link_by = 0;
+    assert!(!rfp::past_deadline(&open, LINK_BY + 3600));
+}
+
+#[test]
+fn submissions_after_the_deadline_are_late() {
+    let rfp = rfp_proposal();
+    let late = submission("aa", LINK_BY + 1, LINK_BY + 1);
+
+    assert!(rfp::past_deadline(
+        &rfp,
+        rfp::submitted_at(&late, &VersionHistory::new())
+    ));
+}
+
+#[test]
+fn submission_time_is_the_first_version_timestamp() {
+    let rfp = rfp_proposal();
+
+    // Submitted on time and edited after the deadline.
+    let first = submission("aa", LINK_BY - 60, 0);
+    let mut edited = submission("aa", LINK_BY + 3600, LINK_BY - 30);
+    edited.version = "2".to_string();
+
+    let mut versions = VersionHistory::new();
+    versions
+        .entry("aa".to_string())
+        .or_default()
+        .insert(1, first);
+
+    assert_eq!(rfp::submitted_at(&edited, &versions), LINK_BY - 60);
+    assert!(!rfp::past_deadline(
+        &rfp,
+        rfp::submitted_at(&edited, &versions)
+    ));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::proposal;
+use politeia::search::SearchIndex;
+
+const INDEX_FILENAME: &str = "index.md";
+
+fn tokens(results: Vec<(String, f64)>) -> Vec<String> {
+    results.into_iter().map(|(token, _)| token).collect()
+}
+
+#[test]
+fn search_requires_every_term() {
+    let proposals = vec![
+        proposal("aa", "Marketing budget", "alice", "Events in 2021."),
+        proposal("bb", "Marketing plan", "bob", "Budget to be decided."),
+        proposal("cc", "Development", "carol", "Nothing related."),
+    ];
+    let index = SearchIndex::build(&proposals, INDEX_FILENAME);
+
+    assert_eq!(tokens(index.search("marketing")), vec!["aa", "bb"]);
+    assert_eq!(tokens(index.search("Marketing BUDGET")), vec!["aa", "bb"]);
+    assert_eq!(tokens(index.search("marketing events")), vec!["aa"]);
+    assert!(index.search("marketing unknown").is_empty());
+    assert!(index.search("  ").is_empty());
+}
+
+#[test]
+fn search_weights_name_over_username_over_body() {
+    let proposals = vec![
+        proposal("body", "Proposal", "alice", "About decred."),
+        proposal("name", "Decred", "bob", "About something."),
+        proposal("user", "Proposal", "decred", "About something."),
+        proposal("none", "Proposal", "carol", "About something."),
+    ];
+    let index = SearchIndex::build(&proposals, INDEX_FILENAME);
+
+    let results = index.search("decred");
+    assert_eq!(tokens(results.clone()), vec!["name", "user", "body"]);
+    assert!((results[0].1 / results[2].1 - 3.0).abs() < 1e-9);
+    assert!((results[1].1 / results[2].1 - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn upsert_replaces_previous_postings() {
+    let mut index = SearchIndex::default();
+    index.upsert(
+        &proposal("aa", "Alpha proposal", "alice", "First body."),
+        INDEX_FILENAME,
+    );
+    index.upsert(
+        &proposal("aa", "Beta proposal", "alice", "Second body."),
+        INDEX_FILENAME,
+    );
+
+    assert!(index.search("alpha").is_empty());
+    assert!(index.search("first").is_empty());
+    assert_eq!(tokens(index.search("beta second")), vec!["aa"]);
+}
+
+#[test]
+fn remove_drops_every_posting() {
+    let proposals = vec![
+        proposal("aa", "Alpha proposal", "alice", "Body."),
+        proposal("bb", "Beta proposal", "bob", "Body."),
+    ];
+    let mut index = SearchIndex::build(&proposals, INDEX_FILENAME);
+
+    index.remove("aa");
+    index.remove("unknown");
+
+    assert!(index.search("alpha").is_empty());
+    assert!(index.search("alice").is_empty());
+    assert_eq!(tokens(index.search("proposal body")), vec!["bb"]);
+}
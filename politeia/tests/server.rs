@@ -0,0 +1,230 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use common::proposal;
+use politeia::{cache::Cache, search::SearchIndex, server, webhook::DeliveryLog};
+use politeia_api::v1::types;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Cache holding `proposals` in the inventory buckets they are paired with.
+async fn cache(proposals: Vec<(&str, types::Proposal)>) -> Cache {
+    let cache = Cache::default();
+    {
+        let mut tokens = cache.tokens.write().await;
+        let mut cached = cache.proposals.write().await;
+        for (bucket, proposal) in proposals {
+            let token = proposal.censorship_record.token.clone();
+            tokens.bucket_mut(bucket).unwrap().push(token.clone());
+            cached.insert(token, proposal);
+        }
+
+        *cache.search.write().await = SearchIndex::build(cached.values(), "index.md");
+    }
+    cache
+}
+
+/// Sends `request` to the API routes, returns the response status and body.
+async fn call(cache: &Cache, request: test::TestRequest) -> (StatusCode, serde_json::Value) {
+    let deliveries = Arc::new(RwLock::new(DeliveryLog::default()));
+    let mut app =
+        test::init_service(App::new().configure(|cfg| server::configure(cfg, cache, &deliveries)))
+            .await;
+
+    let response = test::call_service(&mut app, request.to_request()).await;
+    let status = response.status();
+    let body = test::read_body(response).await;
+
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+async fn get(cache: &Cache, uri: &str) -> (StatusCode, serde_json::Value) {
+    call(cache, test::TestRequest::get().uri(uri)).await
+}
+
+fn result_tokens(body: &serde_json::Value) -> Vec<&str> {
+    body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| {
+            hit["proposal"]["censorshiprecord"]["token"]
+                .as_str()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn search_filters_by_status_and_limit() {
+    let cache = cache(vec![
+        (
+            "pre",
+            proposal("aa", "Treasury report", "alice", "Treasury."),
+        ),
+        ("active", proposal("bb", "Treasury audit", "bob", "Audit.")),
+        (
+            "approved",
+            proposal("cc", "Treasury plan", "carol", "Plan."),
+        ),
+    ])
+    .await;
+
+    let (status, body) = get(&cache, "/api/v1/search?q=treasury").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result_tokens(&body), vec!["aa", "bb", "cc"]);
+
+    let (_, body) = get(&cache, "/api/v1/search?q=treasury&status=active").await;
+    assert_eq!(result_tokens(&body), vec!["bb"]);
+    assert_eq!(body["results"][0]["status"], "active");
+
+    let (_, body) = get(&cache, "/api/v1/search?q=treasury&limit=2").await;
+    assert_eq!(result_tokens(&body), vec!["aa", "bb"]);
+
+    let (status, _) = get(&cache, "/api/v1/search?q=treasury&status=unknown").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn fetch_proposals_accepts_token_prefixes() {
+    let cache = cache(vec![
+        ("pre", proposal("aa11bb22cc33", "First", "alice", "Body.")),
+        ("pre", proposal("aa11bb2ff000", "Second", "bob", "Body.")),
+    ])
+    .await;
+    {
+        let mut policy = cache.policy.write().await;
+        policy.proposal_list_page_size = 20;
+        policy.token_prefix_length = 7;
+    }
+
+    let fetch = |tokens: &[&str]| {
+        test::TestRequest::post()
+            .uri("/api/v1/fetchproposals")
+            .set_json(&serde_json::json!({ "tokens": tokens }))
+    };
+
+    let (status, body) = call(&cache, fetch(&["aa11bb22", "aa11bb2ff000"])).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = body["proposals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|proposal| proposal["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["First", "Second"]);
+
+    let (status, body) = call(&cache, fetch(&["aa11bb2"])).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["context"],
+        serde_json::json!([
+            "aa11bb2: token prefix matches 2 proposals",
+            "aa11bb22cc33",
+            "aa11bb2ff000"
+        ])
+    );
+
+    let (status, _) = call(&cache, fetch(&["aa11"])).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+fn proposal_names(body: &serde_json::Value) -> Vec<&str> {
+    body["proposals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|proposal| proposal["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn list_proposals_pages_a_bucket() {
+    let mut proposals = vec![("approved", proposal("zz", "Approved", "alice", "Body."))];
+    for i in 0..5 {
+        let token = format!("a{}", i);
+        let name = format!("Active {}", i);
+        proposals.push(("active", proposal(&token, &name, "bob", "Body.")));
+    }
+    let cache = cache(proposals).await;
+
+    let (status, body) = get(&cache, "/api/v1/proposals?status=active&per_page=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "active");
+    assert_eq!(body["page"], 1);
+    assert_eq!(body["total"], 5);
+    assert_eq!(proposal_names(&body), vec!["Active 0", "Active 1"]);
+
+    let (_, body) = get(&cache, "/api/v1/proposals?status=active&per_page=2&page=3").await;
+    assert_eq!(proposal_names(&body), vec!["Active 4"]);
+
+    let (_, body) = get(&cache, "/api/v1/proposals?status=approved").await;
+    assert_eq!(proposal_names(&body), vec!["Approved"]);
+
+    let (status, _) = get(&cache, "/api/v1/proposals?status=unknown").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn list_proposals_bounds_page_sizes() {
+    let cache = cache(vec![("pre", proposal("aa", "Pre", "alice", "Body."))]).await;
+
+    // Unknown policy page size.
+    let (_, body) = get(&cache, "/api/v1/proposals?status=pre").await;
+    assert_eq!(body["per_page"], 20);
+
+    // Policy page sizes above the bound are clamped.
+    cache.policy.write().await.proposal_list_page_size = 500;
+    let (status, body) = get(&cache, "/api/v1/proposals?status=pre").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["per_page"], 100);
+
+    for query in &["per_page=0", "per_page=101", "page=0"] {
+        let uri = format!("/api/v1/proposals?status=pre&{}", query);
+        let (status, _) = get(&cache, &uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
+#[tokio::test]
+async fn list_proposals_past_the_last_page_is_empty() {
+    let cache = cache(vec![("pre", proposal("aa", "Pre", "alice", "Body."))]).await;
+
+    for page in &["2", "18446744073709551615"] {
+        let uri = format!("/api/v1/proposals?status=pre&page={}", page);
+        let (status, body) = get(&cache, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 1);
+        assert!(proposal_names(&body).is_empty());
+    }
+}
+
+#[tokio::test]
+async fn proposal_body_without_index_file_is_not_found() {
+    let mut missing = proposal("bb", "Missing", "bob", "Body.");
+    missing.files[0].name = "budget.csv".to_string();
+    let mut tampered = proposal("cc", "Tampered", "carol", "Body.");
+    tampered.files[0].payload = "VGFtcGVyZWQu".to_string();
+
+    let cache = cache(vec![
+        ("pre", proposal("aa", "Rendered", "alice", "# Title")),
+        ("pre", missing),
+        ("pre", tampered),
+    ])
+    .await;
+
+    let request = test::TestRequest::get().uri("/api/v1/proposal/aa/body");
+    let deliveries = Arc::new(RwLock::new(DeliveryLog::default()));
+    let mut app =
+        test::init_service(App::new().configure(|cfg| server::configure(cfg, &cache, &deliveries)))
+            .await;
+    let body = test::read_response(&mut app, request.to_request()).await;
+    assert_eq!(body, "<h1>Title</h1>\n");
+
+    let (status, body) = get(&cache, "/api/v1/proposal/bb/body").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], 5);
+
+    let (status, _) = get(&cache, "/api/v1/proposal/cc/body").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
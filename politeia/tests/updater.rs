@@ -0,0 +1,452 @@
+mod common;
+
+use actix_web::{test, App};
+use common::MockPoliteia;
+use politeia::{
+    cache::{self, Cache},
+    events::ChangeEvent,
+    model::{self, Client},
+    server,
+    webhook::DeliveryLog,
+};
+use politeia_api::v1::{enum_values::ProposalVoteStatus, types};
+use std::sync::Arc;
+use tokio::{sync::RwLock, time};
+
+fn client(mock: &MockPoliteia) -> Client {
+    Client::builder()
+        .host(mock.host())
+        .retry_policy(common::fast_retries())
+        .build()
+        .unwrap()
+}
+
+async fn search_tokens(cache: &Cache, query: &str) -> Vec<String> {
+    let results = cache.search.read().await.search(query);
+    results.into_iter().map(|(token, _)| token).collect()
+}
+
+#[tokio::test]
+async fn refresh_cache_mirrors_politeiawww() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+    let cache = Cache::default();
+
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+
+    let tokens = cache.tokens.read().await.clone();
+    let proposals = cache.proposals.read().await.clone();
+    let vote_summaries = cache.vote_summaries.read().await.clone();
+    let policy = cache.policy.read().await.clone();
+    let comments = cache.comments.read().await.clone();
+
+    {
+        let state = mock.state();
+        assert_eq!(tokens, state.inventory);
+        assert_eq!(proposals, state.proposals);
+        assert_eq!(vote_summaries, state.vote_summaries);
+        assert_eq!(policy, state.policy);
+
+        let token = &state.inventory.active[0];
+        assert_eq!(comments[token].comments, state.comments[token].comments);
+    }
+
+    let stats = cache.stats.read().await.clone();
+    assert_eq!(stats.refreshes, 1);
+    assert_eq!(stats.last_fetched, 4);
+    assert_eq!(stats.last_refused, 0);
+
+    // Nothing changed upstream.
+    assert!(!model::refresh_cache(&mut client, &cache).await.unwrap());
+}
+
+#[tokio::test]
+async fn refresh_cache_publishes_lifecycle_events() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+    let cache = Cache::default();
+
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    let mut events = cache.events.subscribe();
+
+    // Authorizing a vote is not a vote event.
+    let token = mock.state().inventory.pre[0].clone();
+    set_vote_status(&mock, &token, ProposalVoteStatus::Authorized);
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert!(events.try_recv().is_err());
+
+    mock.move_token(&token, "active");
+    set_vote_status(&mock, &token, ProposalVoteStatus::Started);
+    mock.state()
+        .vote_summaries
+        .summaries
+        .get_mut(&token)
+        .unwrap()
+        .end_height = 514000;
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+
+    set_vote_status(&mock, &token, ProposalVoteStatus::Finished);
+    mock.state()
+        .vote_summaries
+        .summaries
+        .get_mut(&token)
+        .unwrap()
+        .approved = true;
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+
+    assert_eq!(
+        received,
+        vec![
+            ChangeEvent::StatusChange {
+                token: token.clone(),
+                from: "pre".to_string(),
+                to: "active".to_string(),
+            },
+            ChangeEvent::VoteStarted {
+                token: token.clone(),
+                end_height: 514000,
+            },
+            ChangeEvent::VoteFinished {
+                token,
+                approved: true,
+            },
+        ]
+    );
+}
+
+fn set_vote_status(mock: &MockPoliteia, token: &str, status: ProposalVoteStatus) {
+    let mut state = mock.state();
+    state
+        .vote_summaries
+        .summaries
+        .get_mut(token)
+        .unwrap()
+        .status = status;
+}
+
+#[tokio::test]
+async fn refresh_cache_retries_failed_tokens() {
+    let mock = MockPoliteia::start();
+    let mut client = client(&mock);
+    let cache = Cache::default();
+
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    let mut events = cache.events.subscribe();
+
+    let token = mock.state().inventory.pre[0].clone();
+    mock.move_token(&token, "approved");
+    mock.state().failing_tokens.insert(token.clone());
+
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(cache.stats.read().await.last_failed, 1);
+    assert_eq!(cache.tokens.read().await.bucket_of(&token), Some("pre"));
+    assert!(events.try_recv().is_err());
+
+    mock.state().failing_tokens.clear();
+
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+    assert_eq!(cache.stats.read().await.last_failed, 0);
+    assert_eq!(
+        cache.tokens.read().await.bucket_of(&token),
+        Some("approved")
+    );
+    assert_eq!(
+        events.try_recv().unwrap(),
+        ChangeEvent::StatusChange {
+            token,
+            from: "pre".to_string(),
+            to: "approved".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn refresh_cache_keeps_failed_tokens_in_place() {
+    let mock = MockPoliteia::start();
+    let tokens: Vec<String> = mock.state().inventory.all_tokens().cloned().collect();
+    for token in &tokens {
+        mock.move_token(token, "pre");
+    }
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    let inventory = cache.tokens.read().await.clone();
+
+    // Pre proposals are refetched every refresh, the first batch fails.
+    let failing = inventory.pre[0].clone();
+    mock.state().failing_tokens.insert(failing);
+
+    assert!(!model::refresh_cache(&mut client, &cache).await.unwrap());
+    assert_eq!(cache.stats.read().await.last_failed, 2);
+    assert_eq!(*cache.tokens.read().await, inventory);
+}
+
+#[tokio::test]
+async fn refresh_cache_refuses_tampered_proposals() {
+    let mock = MockPoliteia::start();
+    let token = mock.state().inventory.approved[0].clone();
+    // Base64 of a body the file digest does not match.
+    mock.state().proposals.get_mut(&token).unwrap().files[0].payload =
+        "VGFtcGVyZWQgcHJvcG9zYWwgYm9keS4=".to_string();
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    assert_eq!(cache.stats.read().await.last_refused, 1);
+    assert!(!cache.proposals.read().await.contains_key(&token));
+    assert_eq!(cache.proposals.read().await.len(), 3);
+}
+
+#[tokio::test]
+async fn refresh_cache_verifies_prior_versions_and_defers_failures() {
+    let mock = MockPoliteia::start();
+    let token = mock.state().inventory.approved[0].clone();
+    {
+        let mut state = mock.state();
+        let proposal = state.proposals.get_mut(&token).unwrap();
+        proposal.version = "3".to_string();
+        let latest = proposal.clone();
+
+        let first = types::Proposal {
+            version: "1".to_string(),
+            ..latest.clone()
+        };
+        let mut tampered = types::Proposal {
+            version: "2".to_string(),
+            ..latest
+        };
+        tampered.files[0].payload = "VGFtcGVyZWQgcHJvcG9zYWwgYm9keS4=".to_string();
+
+        state.versions.insert((token.clone(), 1), first);
+        state.versions.insert((token.clone(), 2), tampered);
+    }
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    let versions: Vec<u32> = cache.versions.read().await[&token]
+        .keys()
+        .copied()
+        .collect();
+    // The latest version is only kept in the proposal cache.
+    assert_eq!(versions, vec![1]);
+    {
+        let proposals = cache.proposals.read().await;
+        let versions = cache.versions.read().await;
+        let latest = cache::proposal_version(&proposals, &versions, &token, 3).unwrap();
+        assert_eq!(latest, &proposals[&token]);
+        assert_eq!(
+            cache::proposal_version(&proposals, &versions, &token, 1)
+                .unwrap()
+                .version,
+            "1"
+        );
+        assert!(cache::proposal_version(&proposals, &versions, &token, 2).is_none());
+    }
+    assert_eq!(cache.version_failures.read().await[&token][&2].failures, 1);
+    assert_eq!(mock.hits("proposal"), 2);
+
+    // The refused version is not refetched on the next refresh.
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(mock.hits("proposal"), 2);
+}
+
+#[tokio::test]
+async fn refresh_cache_fetches_comments_and_defers_failures() {
+    let mock = MockPoliteia::start();
+    let token = mock.state().inventory.active[0].clone();
+    mock.state().failing_comments.insert(token.clone());
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    assert!(!cache.comments.read().await.contains_key(&token));
+    assert_eq!(cache.comment_failures.read().await[&token].failures, 1);
+    let hits = mock.hits("comments");
+    assert!(hits > 0);
+
+    // Failed comments are not refetched before their retry delay.
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(mock.hits("comments"), hits);
+
+    mock.state().failing_comments.clear();
+    cache
+        .comment_failures
+        .write()
+        .await
+        .get_mut(&token)
+        .unwrap()
+        .retry_at = std::time::Instant::now();
+
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+    assert_eq!(cache.comments.read().await[&token].comments.len(), 2);
+    assert!(cache.comment_failures.read().await.is_empty());
+}
+
+#[tokio::test]
+async fn refresh_cache_evicts_proposals_dropped_from_the_inventory() {
+    let mock = MockPoliteia::start();
+    let token = mock.state().inventory.active[0].clone();
+    {
+        let mut state = mock.state();
+        let proposal = state.proposals.get_mut(&token).unwrap();
+        proposal.version = "2".to_string();
+        let first = types::Proposal {
+            version: "1".to_string(),
+            ..proposal.clone()
+        };
+        state.versions.insert((token.clone(), 1), first);
+    }
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    assert!(cache.versions.read().await.contains_key(&token));
+    assert!(cache.comments.read().await.contains_key(&token));
+    assert_eq!(
+        search_tokens(&cache, "marketing campaign").await,
+        vec![token.clone()]
+    );
+
+    mock.remove_token(&token);
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+
+    assert!(!cache.proposals.read().await.contains_key(&token));
+    assert!(!cache.versions.read().await.contains_key(&token));
+    assert!(!cache.comments.read().await.contains_key(&token));
+    assert!(search_tokens(&cache, "marketing campaign").await.is_empty());
+    assert_eq!(cache.proposals.read().await.len(), 3);
+
+    let deliveries = Arc::new(RwLock::new(DeliveryLog::default()));
+    let mut app =
+        test::init_service(App::new().configure(|cfg| server::configure(cfg, &cache, &deliveries)))
+            .await;
+    let request = test::TestRequest::get().uri("/api/v1/stats").to_request();
+    let stats: serde_json::Value = test::read_response_json(&mut app, request).await;
+
+    assert_eq!(stats["refreshes"], 2);
+    // Only the pre proposal is still mutable.
+    assert_eq!(stats["last_fetched"], 1);
+    assert_eq!(stats["last_failed"], 0);
+    assert_eq!(stats["last_evicted"], 1);
+    assert_eq!(stats["total_evicted"], 1);
+}
+
+#[tokio::test]
+async fn refresh_cache_skips_unchanged_finished_proposals() {
+    let mock = MockPoliteia::start();
+    let (pre, active) = {
+        let state = mock.state();
+        (
+            state.inventory.pre[0].clone(),
+            state.inventory.active[0].clone(),
+        )
+    };
+    // Every proposal sits in a bucket where it can no longer change.
+    mock.move_token(&pre, "rejected");
+    mock.move_token(&active, "approved");
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(cache.proposals.read().await.len(), 4);
+    let hits = mock.hits("batch");
+    assert!(hits > 0);
+
+    assert!(!model::refresh_cache(&mut client, &cache).await.unwrap());
+    assert_eq!(mock.hits("batch"), hits);
+    assert_eq!(cache.stats.read().await.last_fetched, 0);
+
+    // Moving bucket marks a finished proposal stale.
+    mock.move_token(&pre, "abandoned");
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(mock.hits("batch"), hits + 1);
+    assert_eq!(cache.stats.read().await.last_fetched, 1);
+}
+
+#[tokio::test]
+async fn refresh_cache_keeps_summaries_of_failed_vote_batches() {
+    let mock = MockPoliteia::start();
+    let (active, approved) = {
+        let state = mock.state();
+        (
+            state.inventory.active[0].clone(),
+            state.inventory.approved[0].clone(),
+        )
+    };
+
+    let mut client = client(&mock);
+    let cache = Cache::default();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+
+    mock.state().failing_summaries.insert(active.clone());
+    mock.state().vote_summaries.best_block += 10;
+    set_vote_status(&mock, &active, ProposalVoteStatus::Finished);
+    set_vote_status(&mock, &approved, ProposalVoteStatus::Invalid);
+
+    assert!(model::refresh_cache(&mut client, &cache).await.unwrap());
+    {
+        let summaries = cache.vote_summaries.read().await;
+        let state = mock.state();
+        assert_eq!(summaries.best_block, state.vote_summaries.best_block);
+        assert_eq!(summaries.summaries.len(), 3);
+        assert_eq!(
+            summaries.summaries[&active].status,
+            ProposalVoteStatus::Started
+        );
+        assert_eq!(
+            summaries.summaries[&approved].status,
+            ProposalVoteStatus::Invalid
+        );
+    }
+
+    mock.state().failing_summaries.clear();
+    model::refresh_cache(&mut client, &cache).await.unwrap();
+    assert_eq!(
+        cache.vote_summaries.read().await.summaries[&active].status,
+        ProposalVoteStatus::Finished
+    );
+}
+
+#[tokio::test]
+async fn update_proposals_saves_snapshot() {
+    let mock = MockPoliteia::start();
+    let cache = Cache::default();
+
+    let path =
+        std::env::temp_dir().join(format!("politeia_updater_test_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    tokio::spawn(model::update_proposals(
+        client(&mock),
+        cache.clone(),
+        Some(path.clone()),
+    ));
+
+    time::timeout(time::Duration::from_secs(10), async {
+        while !path.exists() {
+            time::delay_for(time::Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("updater did not save a snapshot");
+
+    let loaded = Cache::load(&path).await.unwrap();
+    assert_eq!(
+        *loaded.proposals.read().await,
+        *cache.proposals.read().await
+    );
+    assert_eq!(loaded.proposals.read().await.len(), 4);
+
+    std::fs::remove_file(&path).unwrap();
+}